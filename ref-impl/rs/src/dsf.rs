@@ -0,0 +1,631 @@
+//! The DSF (Data Struct Format) value model: a plain, JSON-like document shape
+//! used as the baseline format alongside the richer typed DTXT format (see
+//! `lib.rs`). Unlike DTXT, DSF has no `T`/`F`/`N`/constructor syntax: strings
+//! are double-quoted, object keys are bare identifiers, and literals are the
+//! familiar `true`/`false`/`null`.
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum DSFError {
+    UnexpectedChar(usize, char),
+    UnexpectedEOF,
+    InvalidNumber(String),
+    InvalidLiteral(String),
+    TrailingData(usize),
+}
+
+impl fmt::Display for DSFError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DSFError::UnexpectedChar(pos, ch) => write!(f, "Unexpected char at {}: {}", pos, ch),
+            DSFError::UnexpectedEOF => write!(f, "Unexpected end of file"),
+            DSFError::InvalidNumber(s) => write!(f, "Invalid number: {}", s),
+            DSFError::InvalidLiteral(s) => write!(f, "Invalid literal: {}", s),
+            DSFError::TrailingData(pos) => write!(f, "Trailing data at position {}", pos),
+        }
+    }
+}
+
+impl std::error::Error for DSFError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DSFValue {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Null,
+    /// Arbitrary-precision-in-spirit integer; currently backed by `i64` like
+    /// `DTXTValue::BigInt`, carried losslessly through `parse`/`stringify` as
+    /// a `$bigint:` tagged string rather than `Number(f64)`.
+    BigInt(i64),
+    /// RFC 3339 timestamp, carried as a `$date:` tagged string.
+    Date(String),
+    /// Raw bytes, carried as a `$binary:` tagged hex string.
+    Bytes(Vec<u8>),
+    Array(Vec<DSFValue>),
+    Object(HashMap<String, DSFValue>),
+}
+
+const BIGINT_TAG: &str = "$bigint:";
+const DATE_TAG: &str = "$date:";
+const BINARY_TAG: &str = "$binary:";
+
+fn encode_hex(bytes: &[u8]) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push(HEX[(byte >> 4) as usize] as char);
+        out.push(HEX[(byte & 0x0F) as usize] as char);
+    }
+    out
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(s.len() / 2);
+    let bytes = s.as_bytes();
+    for i in (0..bytes.len()).step_by(2) {
+        let byte = u8::from_str_radix(&s[i..i + 2], 16).ok()?;
+        out.push(byte);
+    }
+    Some(out)
+}
+
+/// Recognize the tagged-string encoding used for [`DSFValue::BigInt`],
+/// [`DSFValue::Date`], and [`DSFValue::Bytes`] so `parse` can recover the
+/// typed variant instead of handing back a plain `String`.
+pub(crate) fn detag_string(s: String) -> DSFValue {
+    if let Some(rest) = s.strip_prefix(BIGINT_TAG) {
+        if let Ok(n) = rest.parse::<i64>() {
+            return DSFValue::BigInt(n);
+        }
+    } else if let Some(rest) = s.strip_prefix(DATE_TAG) {
+        return DSFValue::Date(rest.to_string());
+    } else if let Some(rest) = s.strip_prefix(BINARY_TAG) {
+        if let Some(bytes) = decode_hex(rest) {
+            return DSFValue::Bytes(bytes);
+        }
+    }
+    DSFValue::String(s)
+}
+
+/// Knobs for [`parse_with`], the Hjson-style lenient superset of strict DSF.
+/// The zero-config [`parse`] function is equivalent to
+/// `parse_with(input, ParseOptions::strict())`; round-tripping a lenient
+/// document through `stringify` always emits canonical strict DSF, since the
+/// parsed `DSFValue` tree carries no memory of which syntax produced it.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    /// Allow `// line` and `/* block */` comments.
+    pub comments: bool,
+    /// Allow a trailing `,` before a closing `}`/`]`.
+    pub trailing_commas: bool,
+    /// Allow bare identifier object keys (`key: 1`) in addition to quoted
+    /// string keys (`"key": 1`). Strict DSF already allows this by default;
+    /// the flag exists so a caller that wants JSON-style quoted-only keys
+    /// can set it to `false`.
+    pub unquoted_keys: bool,
+}
+
+impl ParseOptions {
+    pub const fn strict() -> Self {
+        ParseOptions {
+            comments: false,
+            trailing_commas: false,
+            unquoted_keys: true,
+        }
+    }
+
+    pub const fn lenient() -> Self {
+        ParseOptions {
+            comments: true,
+            trailing_commas: true,
+            unquoted_keys: true,
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+const IDENT_FIRST: u8 = 1 << 0;
+const IDENT_OTHER: u8 = 1 << 1;
+const DIGIT: u8 = 1 << 2;
+const NUMBER_CHAR: u8 = 1 << 3;
+const WHITESPACE: u8 = 1 << 4;
+
+const fn build_class_table() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0usize;
+    while i < 256 {
+        let b = i as u8;
+        let mut flags = 0u8;
+        if (b >= b'a' && b <= b'z') || (b >= b'A' && b <= b'Z') || b == b'_' {
+            flags |= IDENT_FIRST | IDENT_OTHER;
+        }
+        if b.is_ascii_digit() {
+            flags |= IDENT_OTHER | DIGIT | NUMBER_CHAR;
+        }
+        if b == b'.' || b == b'e' || b == b'E' || b == b'+' || b == b'-' {
+            flags |= NUMBER_CHAR;
+        }
+        if b == b' ' || b == b'\t' || b == b'\r' || b == b'\n' {
+            flags |= WHITESPACE;
+        }
+        table[i] = flags;
+        i += 1;
+    }
+    table
+}
+
+/// Precomputed byte classification used by the hot scanning loops
+/// (`skip_whitespace`, `parse_key`, `parse_number`, the `parse_value`
+/// dispatch) so each byte is classified with a single table lookup instead
+/// of a chain of `is_ascii_*`/`matches!` range checks.
+const CLASS: [u8; 256] = build_class_table();
+
+#[inline(always)]
+fn class(b: u8) -> u8 {
+    CLASS[b as usize]
+}
+
+pub struct DSFParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    options: ParseOptions,
+}
+
+impl<'a> DSFParser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::with_options(input, ParseOptions::strict())
+    }
+
+    pub fn with_options(input: &'a str, options: ParseOptions) -> Self {
+        Self {
+            input: input.as_bytes(),
+            pos: 0,
+            options,
+        }
+    }
+
+    #[inline]
+    fn current(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        self.pos += 1;
+    }
+
+    #[inline(always)]
+    fn skip_whitespace(&mut self) {
+        loop {
+            while self.current().is_some_and(|b| class(b) & WHITESPACE != 0) {
+                self.advance();
+            }
+            if !self.options.comments {
+                return;
+            }
+            if self.current() == Some(b'/') && self.input.get(self.pos + 1) == Some(&b'/') {
+                self.pos += 2;
+                while !matches!(self.current(), Some(b'\n') | None) {
+                    self.advance();
+                }
+                continue;
+            }
+            if self.current() == Some(b'/') && self.input.get(self.pos + 1) == Some(&b'*') {
+                self.pos += 2;
+                while self.current().is_some() && !(self.current() == Some(b'*') && self.input.get(self.pos + 1) == Some(&b'/')) {
+                    self.advance();
+                }
+                self.pos = (self.pos + 2).min(self.input.len());
+                continue;
+            }
+            return;
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<DSFValue, DSFError> {
+        self.skip_whitespace();
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos < self.input.len() {
+            return Err(DSFError::TrailingData(self.pos));
+        }
+        Ok(value)
+    }
+
+    fn parse_value(&mut self) -> Result<DSFValue, DSFError> {
+        self.skip_whitespace();
+        match self.current() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => self.parse_string().map(detag_string),
+            Some(b't') => self.parse_literal("true", DSFValue::Bool(true)),
+            Some(b'f') => self.parse_literal("false", DSFValue::Bool(false)),
+            Some(b'n') => self.parse_literal("null", DSFValue::Null),
+            Some(b'-') => self.parse_number().map(DSFValue::Number),
+            Some(ch) if class(ch) & DIGIT != 0 => self.parse_number().map(DSFValue::Number),
+            Some(ch) => Err(DSFError::UnexpectedChar(self.pos, ch as char)),
+            None => Err(DSFError::UnexpectedEOF),
+        }
+    }
+
+    fn parse_literal(&mut self, lit: &str, value: DSFValue) -> Result<DSFValue, DSFError> {
+        let end = self.pos + lit.len();
+        if end <= self.input.len() && &self.input[self.pos..end] == lit.as_bytes() {
+            self.pos = end;
+            Ok(value)
+        } else {
+            Err(DSFError::InvalidLiteral(lit.to_string()))
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<DSFValue, DSFError> {
+        self.advance(); // skip '{'
+        let mut map = HashMap::new();
+
+        self.skip_whitespace();
+        while self.current() != Some(b'}') {
+            let key = self.parse_key()?;
+            self.skip_whitespace();
+
+            if self.current() != Some(b':') {
+                return Err(DSFError::UnexpectedChar(
+                    self.pos,
+                    self.current().map(|c| c as char).unwrap_or('\0'),
+                ));
+            }
+            self.advance(); // skip ':'
+
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_whitespace();
+            if self.current() == Some(b',') {
+                self.advance();
+                self.skip_whitespace();
+                if self.current() == Some(b'}') && !self.options.trailing_commas {
+                    return Err(DSFError::UnexpectedChar(self.pos, '}'));
+                }
+            }
+        }
+
+        self.advance(); // skip '}'
+        Ok(DSFValue::Object(map))
+    }
+
+    fn parse_array(&mut self) -> Result<DSFValue, DSFError> {
+        self.advance(); // skip '['
+        let mut arr = Vec::new();
+
+        self.skip_whitespace();
+        while self.current() != Some(b']') {
+            arr.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            if self.current() == Some(b',') {
+                self.advance();
+                self.skip_whitespace();
+                if self.current() == Some(b']') && !self.options.trailing_commas {
+                    return Err(DSFError::UnexpectedChar(self.pos, ']'));
+                }
+            }
+        }
+
+        self.advance(); // skip ']'
+        Ok(DSFValue::Array(arr))
+    }
+
+    fn parse_key(&mut self) -> Result<String, DSFError> {
+        if self.current() == Some(b'"') {
+            return self.parse_string();
+        }
+        if !self.options.unquoted_keys {
+            return Err(DSFError::UnexpectedChar(
+                self.pos,
+                self.current().map(|c| c as char).unwrap_or('\0'),
+            ));
+        }
+        let start = self.pos;
+        while self.current().is_some_and(|b| class(b) & IDENT_OTHER != 0) {
+            self.advance();
+        }
+        if self.pos == start {
+            return Err(DSFError::UnexpectedChar(
+                self.pos,
+                self.current().map(|c| c as char).unwrap_or('\0'),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&self.input[start..self.pos]).into_owned())
+    }
+
+    fn parse_string(&mut self) -> Result<String, DSFError> {
+        self.advance(); // skip opening '"'
+        let mut s = String::new();
+        loop {
+            match self.current() {
+                Some(b'"') => {
+                    self.advance();
+                    return Ok(s);
+                }
+                Some(b'\\') => {
+                    self.advance();
+                    match self.current() {
+                        Some(b'"') => s.push('"'),
+                        Some(b'\\') => s.push('\\'),
+                        Some(b'n') => s.push('\n'),
+                        Some(b't') => s.push('\t'),
+                        Some(b'r') => s.push('\r'),
+                        Some(ch) => return Err(DSFError::UnexpectedChar(self.pos, ch as char)),
+                        None => return Err(DSFError::UnexpectedEOF),
+                    }
+                    self.advance();
+                }
+                Some(ch) => {
+                    s.push(ch as char);
+                    self.advance();
+                }
+                None => return Err(DSFError::UnexpectedEOF),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<f64, DSFError> {
+        let start = self.pos;
+        if self.current() == Some(b'-') {
+            self.advance();
+        }
+        while self.current().is_some_and(|b| class(b) & DIGIT != 0) {
+            self.advance();
+        }
+        if self.current() == Some(b'.') {
+            self.advance();
+            while self.current().is_some_and(|b| class(b) & DIGIT != 0) {
+                self.advance();
+            }
+        }
+        if matches!(self.current(), Some(b'e') | Some(b'E')) {
+            self.advance();
+            if matches!(self.current(), Some(b'+') | Some(b'-')) {
+                self.advance();
+            }
+            while self.current().is_some_and(|b| class(b) & DIGIT != 0) {
+                self.advance();
+            }
+        }
+        let num_str = std::str::from_utf8(&self.input[start..self.pos])
+            .map_err(|_| DSFError::InvalidNumber("invalid utf8".to_string()))?;
+        num_str
+            .parse::<f64>()
+            .map_err(|_| DSFError::InvalidNumber(num_str.to_string()))
+    }
+}
+
+pub fn parse(input: &str) -> Result<DSFValue, DSFError> {
+    #[cfg(feature = "simd")]
+    {
+        crate::dsf_simd::parse(input)
+    }
+    #[cfg(not(feature = "simd"))]
+    {
+        let mut parser = DSFParser::new(input);
+        parser.parse()
+    }
+}
+
+/// Parse with a relaxed or restricted [`ParseOptions`] instead of the
+/// strict default; bypasses the SIMD fast path, which only implements
+/// strict DSF.
+pub fn parse_with(input: &str, options: ParseOptions) -> Result<DSFValue, DSFError> {
+    let mut parser = DSFParser::with_options(input, options);
+    parser.parse()
+}
+
+pub fn stringify(value: &DSFValue, indent: Option<&str>) -> String {
+    let mut out = String::with_capacity(1024);
+    stringify_value(value, &mut out, indent, 0);
+    out
+}
+
+fn stringify_value(value: &DSFValue, out: &mut String, indent: Option<&str>, level: usize) {
+    match value {
+        DSFValue::String(s) => {
+            out.push('"');
+            for ch in s.chars() {
+                match ch {
+                    '"' => out.push_str("\\\""),
+                    '\\' => out.push_str("\\\\"),
+                    '\n' => out.push_str("\\n"),
+                    '\t' => out.push_str("\\t"),
+                    '\r' => out.push_str("\\r"),
+                    c => out.push(c),
+                }
+            }
+            out.push('"');
+        }
+        DSFValue::Number(n) => {
+            let mut buf = ryu::Buffer::new();
+            out.push_str(buf.format(*n));
+        }
+        DSFValue::Bool(true) => out.push_str("true"),
+        DSFValue::Bool(false) => out.push_str("false"),
+        DSFValue::Null => out.push_str("null"),
+        DSFValue::BigInt(n) => {
+            out.push('"');
+            out.push_str(BIGINT_TAG);
+            out.push_str(&n.to_string());
+            out.push('"');
+        }
+        DSFValue::Date(s) => {
+            out.push('"');
+            out.push_str(DATE_TAG);
+            out.push_str(s);
+            out.push('"');
+        }
+        DSFValue::Bytes(bytes) => {
+            out.push('"');
+            out.push_str(BINARY_TAG);
+            out.push_str(&encode_hex(bytes));
+            out.push('"');
+        }
+        DSFValue::Array(arr) => {
+            if arr.is_empty() {
+                out.push_str("[]");
+                return;
+            }
+            out.push('[');
+            if let Some(ind) = indent {
+                out.push('\n');
+                for item in arr.iter() {
+                    for _ in 0..=level {
+                        out.push_str(ind);
+                    }
+                    stringify_value(item, out, indent, level + 1);
+                    out.push_str(",\n");
+                }
+                for _ in 0..level {
+                    out.push_str(ind);
+                }
+            } else {
+                for (i, item) in arr.iter().enumerate() {
+                    stringify_value(item, out, indent, level + 1);
+                    if i < arr.len() - 1 {
+                        out.push(',');
+                    }
+                }
+            }
+            out.push(']');
+        }
+        DSFValue::Object(map) => {
+            if map.is_empty() {
+                out.push_str("{}");
+                return;
+            }
+            out.push('{');
+            let mut keys: Vec<_> = map.keys().collect();
+            keys.sort_unstable();
+
+            if let Some(ind) = indent {
+                out.push('\n');
+                for key in &keys {
+                    for _ in 0..=level {
+                        out.push_str(ind);
+                    }
+                    out.push_str(key);
+                    out.push_str(": ");
+                    stringify_value(&map[*key], out, indent, level + 1);
+                    out.push_str(",\n");
+                }
+                for _ in 0..level {
+                    out.push_str(ind);
+                }
+            } else {
+                for (i, key) in keys.iter().enumerate() {
+                    out.push_str(key);
+                    out.push(':');
+                    stringify_value(&map[*key], out, indent, level + 1);
+                    if i < keys.len() - 1 {
+                        out.push(',');
+                    }
+                }
+            }
+            out.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_object() {
+        let input = r#"{name: "John", age: 30}"#;
+        let result = parse(input).unwrap();
+        if let DSFValue::Object(map) = result {
+            assert_eq!(map.len(), 2);
+        } else {
+            panic!("Expected object");
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let input = r#"{a: 1, b: [true, false, null], c: "hi"}"#;
+        let value = parse(input).unwrap();
+        let out = stringify(&value, None);
+        let reparsed = parse(&out).unwrap();
+        assert_eq!(value, reparsed);
+    }
+
+    #[test]
+    fn test_typed_scalar_roundtrip() {
+        let value = DSFValue::Object(HashMap::from([
+            ("n".to_string(), DSFValue::BigInt(123456789012345)),
+            ("d".to_string(), DSFValue::Date("2024-01-01T00:00:00Z".to_string())),
+            ("b".to_string(), DSFValue::Bytes(vec![0xDE, 0xAD, 0xBE, 0xEF])),
+        ]));
+        let out = stringify(&value, None);
+        let reparsed = parse(&out).unwrap();
+        assert_eq!(value, reparsed);
+    }
+
+    #[test]
+    fn test_bigint_beats_float_precision() {
+        let out = stringify(&DSFValue::BigInt(9007199254740993), None);
+        assert_eq!(out, r#""$bigint:9007199254740993""#);
+    }
+
+    #[test]
+    fn test_strict_rejects_comments_and_trailing_commas() {
+        assert!(parse("{a: 1, // trailing\n}").is_err());
+        assert!(parse("{a: 1,}").is_err());
+        assert!(parse("[1, 2,]").is_err());
+    }
+
+    #[test]
+    fn test_lenient_allows_comments_and_trailing_commas() {
+        let input = r#"{
+            // a comment
+            a: 1, /* block */
+            b: [1, 2, 3,],
+        }"#;
+        let value = parse_with(input, ParseOptions::lenient()).unwrap();
+        if let DSFValue::Object(map) = value {
+            assert_eq!(map.len(), 2);
+        } else {
+            panic!("Expected object");
+        }
+    }
+
+    #[test]
+    fn test_lenient_roundtrips_to_canonical_strict_dsf() {
+        let input = r#"{a: 1, b: [1, 2,], /* note */}"#;
+        let value = parse_with(input, ParseOptions::lenient()).unwrap();
+        let out = stringify(&value, None);
+        assert_eq!(parse(&out).unwrap(), value);
+    }
+
+    #[test]
+    fn test_unquoted_keys_false_requires_quoted_keys() {
+        let opts = ParseOptions {
+            unquoted_keys: false,
+            ..ParseOptions::strict()
+        };
+        assert!(parse_with("{a: 1}", opts).is_err());
+        let value = parse_with(r#"{"a": 1}"#, opts).unwrap();
+        if let DSFValue::Object(map) = value {
+            assert_eq!(map.get("a"), Some(&DSFValue::Number(1.0)));
+        } else {
+            panic!("Expected object");
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//! Schema validation for DSF documents: the DSF equivalent of JSON Schema.
+//!
+//! A schema is itself a DSF document, e.g.:
+//!
+//! ```text
+//! {
+//!   type: "object",
+//!   required: ["id", "score"],
+//!   properties: {
+//!     id: {type: "number"},
+//!     score: {type: "number", min: 0, max: 100},
+//!     tags: {type: "array", items: {type: "string"}},
+//!     role: {type: "string", enum: ["admin", "member"]}
+//!   }
+//! }
+//! ```
+//!
+//! [`Schema::compile`] parses that shape once into a [`Schema`] you can run
+//! against many instances with [`Schema::validate`], which collects every
+//! failure (rather than failing fast) with JSON-pointer-style paths like
+//! `/entries/17/score`.
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+
+use crate::dsf::DSFValue;
+
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The schema document itself is malformed, at the given JSON-pointer path.
+    Invalid(String, String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaError::Invalid(path, msg) => write!(f, "invalid schema at {}: {}", path, msg),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TypeConstraint {
+    String,
+    Number,
+    Bool,
+    Null,
+    Array,
+    Object,
+    Any,
+}
+
+#[derive(Debug, Clone, Default)]
+struct NumberConstraints {
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct StringConstraints {
+    min_len: Option<usize>,
+    max_len: Option<usize>,
+    pattern: Option<Regex>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Schema {
+    ty: TypeConstraint,
+    number: NumberConstraints,
+    string: StringConstraints,
+    enum_values: Option<Vec<DSFValue>>,
+    required: Vec<String>,
+    properties: HashMap<String, Schema>,
+    items: Option<Box<Schema>>,
+}
+
+impl Schema {
+    /// Compile a schema document (itself a `DSFValue`, typically produced by
+    /// `dsf::parse`) into a reusable validator.
+    pub fn compile(doc: &DSFValue) -> Result<Schema, SchemaError> {
+        Self::compile_at(doc, "")
+    }
+
+    fn compile_at(doc: &DSFValue, path: &str) -> Result<Schema, SchemaError> {
+        let DSFValue::Object(map) = doc else {
+            return Err(SchemaError::Invalid(
+                path.to_string(),
+                "schema node must be an object".to_string(),
+            ));
+        };
+
+        let ty = match map.get("type") {
+            Some(DSFValue::String(s)) => match s.as_str() {
+                "string" => TypeConstraint::String,
+                "number" => TypeConstraint::Number,
+                "bool" | "boolean" => TypeConstraint::Bool,
+                "null" => TypeConstraint::Null,
+                "array" => TypeConstraint::Array,
+                "object" => TypeConstraint::Object,
+                "any" => TypeConstraint::Any,
+                other => {
+                    return Err(SchemaError::Invalid(
+                        path.to_string(),
+                        format!("unknown type: {}", other),
+                    ))
+                }
+            },
+            Some(_) => {
+                return Err(SchemaError::Invalid(
+                    path.to_string(),
+                    "`type` must be a string".to_string(),
+                ))
+            }
+            None => TypeConstraint::Any,
+        };
+
+        let number = NumberConstraints {
+            min: number_field(map, "min"),
+            max: number_field(map, "max"),
+        };
+
+        let string = StringConstraints {
+            min_len: number_field(map, "minLength").map(|n| n as usize),
+            max_len: number_field(map, "maxLength").map(|n| n as usize),
+            pattern: match map.get("pattern") {
+                Some(DSFValue::String(s)) => Some(Regex::new(s).map_err(|e| {
+                    SchemaError::Invalid(
+                        format!("{}/pattern", path),
+                        format!("invalid regex: {}", e),
+                    )
+                })?),
+                _ => None,
+            },
+        };
+
+        let enum_values = match map.get("enum") {
+            Some(DSFValue::Array(items)) => Some(items.clone()),
+            _ => None,
+        };
+
+        let required = match map.get("required") {
+            Some(DSFValue::Array(items)) => items
+                .iter()
+                .filter_map(|v| match v {
+                    DSFValue::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let mut properties = HashMap::new();
+        if let Some(DSFValue::Object(props)) = map.get("properties") {
+            for (key, sub) in props {
+                let sub_path = format!("{}/properties/{}", path, key);
+                properties.insert(key.clone(), Self::compile_at(sub, &sub_path)?);
+            }
+        }
+
+        let items = match map.get("items") {
+            Some(sub) => Some(Box::new(Self::compile_at(
+                sub,
+                &format!("{}/items", path),
+            )?)),
+            None => None,
+        };
+
+        Ok(Schema {
+            ty,
+            number,
+            string,
+            enum_values,
+            required,
+            properties,
+            items,
+        })
+    }
+
+    /// Validate `value` against this schema, returning every failure found
+    /// rather than stopping at the first one.
+    pub fn validate(&self, value: &DSFValue) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        self.validate_at(value, "", &mut errors);
+        errors
+    }
+
+    fn validate_at(&self, value: &DSFValue, path: &str, errors: &mut Vec<ValidationError>) {
+        if !self.type_matches(value) {
+            errors.push(ValidationError {
+                path: pointer(path),
+                message: format!("expected {}, got {}", self.ty_name(), value_kind(value)),
+            });
+            return;
+        }
+
+        if let Some(allowed) = &self.enum_values {
+            if !allowed.contains(value) {
+                errors.push(ValidationError {
+                    path: pointer(path),
+                    message: "value is not one of the allowed enum values".to_string(),
+                });
+            }
+        }
+
+        match value {
+            DSFValue::Number(n) => {
+                if let Some(min) = self.number.min {
+                    if *n < min {
+                        errors.push(ValidationError {
+                            path: pointer(path),
+                            message: format!("{} is less than minimum {}", n, min),
+                        });
+                    }
+                }
+                if let Some(max) = self.number.max {
+                    if *n > max {
+                        errors.push(ValidationError {
+                            path: pointer(path),
+                            message: format!("{} is greater than maximum {}", n, max),
+                        });
+                    }
+                }
+            }
+            DSFValue::String(s) => {
+                if let Some(min_len) = self.string.min_len {
+                    if s.chars().count() < min_len {
+                        errors.push(ValidationError {
+                            path: pointer(path),
+                            message: format!("string shorter than minLength {}", min_len),
+                        });
+                    }
+                }
+                if let Some(max_len) = self.string.max_len {
+                    if s.chars().count() > max_len {
+                        errors.push(ValidationError {
+                            path: pointer(path),
+                            message: format!("string longer than maxLength {}", max_len),
+                        });
+                    }
+                }
+                if let Some(pattern) = &self.string.pattern {
+                    if !pattern.is_match(s) {
+                        errors.push(ValidationError {
+                            path: pointer(path),
+                            message: format!("string does not match pattern `{}`", pattern.as_str()),
+                        });
+                    }
+                }
+            }
+            DSFValue::Array(items) => {
+                if let Some(item_schema) = &self.items {
+                    for (i, item) in items.iter().enumerate() {
+                        item_schema.validate_at(item, &format!("{}/{}", path, i), errors);
+                    }
+                }
+            }
+            DSFValue::Object(map) => {
+                for key in &self.required {
+                    if !map.contains_key(key) {
+                        errors.push(ValidationError {
+                            path: pointer(path),
+                            message: format!("missing required key `{}`", key),
+                        });
+                    }
+                }
+                for (key, sub_schema) in &self.properties {
+                    if let Some(sub_value) = map.get(key) {
+                        sub_schema.validate_at(sub_value, &format!("{}/{}", path, key), errors);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn type_matches(&self, value: &DSFValue) -> bool {
+        matches!(
+            (&self.ty, value),
+            (TypeConstraint::Any, _)
+                | (TypeConstraint::String, DSFValue::String(_))
+                | (TypeConstraint::Number, DSFValue::Number(_))
+                | (TypeConstraint::Bool, DSFValue::Bool(_))
+                | (TypeConstraint::Null, DSFValue::Null)
+                | (TypeConstraint::Array, DSFValue::Array(_))
+                | (TypeConstraint::Object, DSFValue::Object(_))
+        )
+    }
+
+    fn ty_name(&self) -> &'static str {
+        match self.ty {
+            TypeConstraint::String => "string",
+            TypeConstraint::Number => "number",
+            TypeConstraint::Bool => "bool",
+            TypeConstraint::Null => "null",
+            TypeConstraint::Array => "array",
+            TypeConstraint::Object => "object",
+            TypeConstraint::Any => "any",
+        }
+    }
+}
+
+fn number_field(map: &HashMap<String, DSFValue>, key: &str) -> Option<f64> {
+    match map.get(key) {
+        Some(DSFValue::Number(n)) => Some(*n),
+        _ => None,
+    }
+}
+
+fn value_kind(value: &DSFValue) -> &'static str {
+    match value {
+        DSFValue::String(_) => "string",
+        DSFValue::Number(_) => "number",
+        DSFValue::Bool(_) => "bool",
+        DSFValue::Null => "null",
+        DSFValue::BigInt(_) => "bigint",
+        DSFValue::Date(_) => "date",
+        DSFValue::Bytes(_) => "bytes",
+        DSFValue::Array(_) => "array",
+        DSFValue::Object(_) => "object",
+    }
+}
+
+fn pointer(path: &str) -> String {
+    if path.is_empty() {
+        "/".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsf;
+
+    #[test]
+    fn flags_missing_required_and_out_of_range() {
+        let schema_doc = dsf::parse(
+            r#"{type: "object", required: ["id", "score"], properties: {score: {type: "number", min: 0, max: 100}}}"#,
+        )
+        .unwrap();
+        let schema = Schema::compile(&schema_doc).unwrap();
+
+        let instance = dsf::parse(r#"{score: 150}"#).unwrap();
+        let errors = schema.validate(&instance);
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.message.contains("id")));
+        assert!(errors.iter().any(|e| e.message.contains("greater than maximum")));
+    }
+
+    #[test]
+    fn nested_array_item_errors_have_pointer_paths() {
+        let schema_doc = dsf::parse(
+            r#"{type: "object", properties: {entries: {type: "array", items: {type: "object", properties: {score: {type: "number", max: 10}}}}}}"#,
+        )
+        .unwrap();
+        let schema = Schema::compile(&schema_doc).unwrap();
+
+        let instance = dsf::parse(r#"{entries: [{score: 1}, {score: 99}]}"#).unwrap();
+        let errors = schema.validate(&instance);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/entries/1/score");
+    }
+}
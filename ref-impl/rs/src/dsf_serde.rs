@@ -0,0 +1,544 @@
+//! Optional `serde` integration for [`DSFValue`](crate::dsf::DSFValue) and
+//! [`DTXTValue`](crate::DTXTValue), gated behind the `serde` feature.
+//!
+//! This lets arbitrary `#[derive(Serialize)]`/`#[derive(Deserialize)]` types
+//! round-trip through DSF the way they do through `serde_json`:
+//!
+//! ```ignore
+//! let s = dsf::to_string(&my_struct)?;
+//! let back: MyStruct = dsf::from_str(&s)?;
+//! ```
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, Serializer as SerdeSerializer,
+};
+
+use crate::dsf::{self, DSFValue};
+use crate::DTXTValue;
+
+#[derive(Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl From<dsf::DSFError> for Error {
+    fn from(e: dsf::DSFError) -> Self {
+        Error(e.to_string())
+    }
+}
+
+impl Serialize for DSFValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: SerdeSerializer,
+    {
+        match self {
+            DSFValue::String(s) => serializer.serialize_str(s),
+            DSFValue::Number(n) => serializer.serialize_f64(*n),
+            DSFValue::Bool(b) => serializer.serialize_bool(*b),
+            DSFValue::Null => serializer.serialize_unit(),
+            DSFValue::BigInt(n) => serializer.serialize_i64(*n),
+            DSFValue::Date(s) => serializer.serialize_str(s),
+            DSFValue::Bytes(b) => serializer.serialize_bytes(b),
+            DSFValue::Array(arr) => {
+                let mut seq = serializer.serialize_seq(Some(arr.len()))?;
+                for item in arr {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            DSFValue::Object(map) => {
+                let mut m = serializer.serialize_map(Some(map.len()))?;
+                for (k, v) in map {
+                    m.serialize_entry(k, v)?;
+                }
+                m.end()
+            }
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DSFValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = DSFValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a valid DSF value")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(DSFValue::Bool(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(DSFValue::Number(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(DSFValue::Number(v as f64))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(DSFValue::Number(v as f64))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(DSFValue::String(v.to_string()))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+                Ok(DSFValue::String(v))
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(DSFValue::Null)
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E> {
+                Ok(DSFValue::Null)
+            }
+
+            fn visit_some<D>(self, d: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                serde::Deserialize::deserialize(d)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut arr = Vec::new();
+                while let Some(item) = seq.next_element()? {
+                    arr.push(item);
+                }
+                Ok(DSFValue::Array(arr))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut out = HashMap::new();
+                while let Some((k, v)) = map.next_entry()? {
+                    out.insert(k, v);
+                }
+                Ok(DSFValue::Object(out))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Serialize any `T: Serialize` to a DSF-formatted string, the way
+/// `serde_json::to_string` does for JSON.
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, Error> {
+    let dsf_value = value.serialize(ValueSerializer)?;
+    Ok(dsf::stringify(&dsf_value, None))
+}
+
+/// Parse a DSF-formatted string directly into any `T: Deserialize`.
+pub fn from_str<T: DeserializeOwned>(input: &str) -> Result<T, Error> {
+    let value = dsf::parse(input)?;
+    T::deserialize(value.into_deserializer())
+}
+
+impl<'de> IntoDeserializer<'de, Error> for DSFValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for DSFValue {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            DSFValue::String(s) => visitor.visit_string(s),
+            DSFValue::Number(n) => visitor.visit_f64(n),
+            DSFValue::Bool(b) => visitor.visit_bool(b),
+            DSFValue::Null => visitor.visit_unit(),
+            DSFValue::BigInt(n) => visitor.visit_i64(n),
+            DSFValue::Date(s) => visitor.visit_string(s),
+            DSFValue::Bytes(b) => visitor.visit_byte_buf(b),
+            DSFValue::Array(arr) => visitor.visit_seq(de::value::SeqDeserializer::new(arr.into_iter())),
+            DSFValue::Object(map) => visitor.visit_map(de::value::MapDeserializer::new(map.into_iter())),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// A minimal `serde::Serializer` that builds a [`DSFValue`] tree in memory,
+/// the intermediate step `to_string` uses before handing off to
+/// [`dsf::stringify`].
+struct ValueSerializer;
+
+struct SeqSerializer {
+    items: Vec<DSFValue>,
+}
+
+struct MapSerializer {
+    map: HashMap<String, DSFValue>,
+    next_key: Option<String>,
+}
+
+impl SerdeSerializer for ValueSerializer {
+    type Ok = DSFValue;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Bool(v))
+    }
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v as f64))
+    }
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Number(v))
+    }
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::String(v.to_string()))
+    }
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::String(v.to_string()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Bytes(v.to_vec()))
+    }
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Null)
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Null)
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Null)
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::String(variant.to_string()))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let mut map = HashMap::new();
+        map.insert(variant.to_string(), value.serialize(ValueSerializer)?);
+        Ok(DSFValue::Object(map))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SeqSerializer {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(MapSerializer {
+            map: HashMap::new(),
+            next_key: None,
+        })
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(MapSerializer {
+            map: HashMap::with_capacity(len),
+            next_key: None,
+        })
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.serialize_struct(_name, len)
+    }
+}
+
+impl SerializeSeq for SeqSerializer {
+    type Ok = DSFValue;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Array(self.items))
+    }
+}
+
+impl serde::ser::SerializeTuple for SeqSerializer {
+    type Ok = DSFValue;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = DSFValue;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = DSFValue;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl SerializeMap for MapSerializer {
+    type Ok = DSFValue;
+    type Error = Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        let key_value = key.serialize(ValueSerializer)?;
+        let key_str = match key_value {
+            DSFValue::String(s) => s,
+            other => dsf::stringify(&other, None),
+        };
+        self.next_key = Some(key_str);
+        Ok(())
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| Error("serialize_value called before serialize_key".to_string()))?;
+        self.map.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Object(self.map))
+    }
+}
+
+impl serde::ser::SerializeStruct for MapSerializer {
+    type Ok = DSFValue;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.map.insert(key.to_string(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(DSFValue::Object(self.map))
+    }
+}
+
+impl serde::ser::SerializeStructVariant for MapSerializer {
+    type Ok = DSFValue;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        serde::ser::SerializeStruct::serialize_field(self, key, value)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        serde::ser::SerializeStruct::end(self)
+    }
+}
+
+// `DTXTValue` borrows from its input, so (unlike `DSFValue`) it can only
+// reasonably support the `Serialize` half of serde: there is no buffer to
+// borrow owned data *into* on the way back out of an arbitrary `Deserializer`.
+// Callers who need a round trip should deserialize through `DSFValue` (or an
+// owned copy of their own type) instead.
+impl<'a> Serialize for DTXTValue<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: SerdeSerializer,
+    {
+        match self {
+            DTXTValue::String(s) => serializer.serialize_str(s),
+            DTXTValue::Number(n) => serializer.serialize_f64(*n),
+            DTXTValue::Bool(b) => serializer.serialize_bool(*b),
+            DTXTValue::Null => serializer.serialize_unit(),
+            // Arbitrary-precision, so serialize as a decimal string rather
+            // than risking truncation through a fixed-width serde number type.
+            DTXTValue::BigInt(n) => serializer.serialize_str(&n.to_string()),
+            DTXTValue::Date(s) => serializer.serialize_str(s),
+            DTXTValue::Bytes(b) => serializer.serialize_bytes(b),
+            DTXTValue::Array(arr) => {
+                let mut seq = serializer.serialize_seq(Some(arr.len()))?;
+                for item in arr {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            DTXTValue::Object(map) => {
+                let mut m = serializer.serialize_map(Some(map.len()))?;
+                for (k, v) in map {
+                    m.serialize_entry(k, v)?;
+                }
+                m.end()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serializer as _};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        id: u32,
+        name: String,
+        active: bool,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn round_trips_derived_struct() {
+        let value = Sample {
+            id: 7,
+            name: "widget".to_string(),
+            active: true,
+            tags: vec!["a".to_string(), "b".to_string()],
+        };
+        let s = to_string(&value).unwrap();
+        let back: Sample = from_str(&s).unwrap();
+        assert_eq!(value, back);
+    }
+
+    #[test]
+    fn round_trips_bytes_value() {
+        let value = DSFValue::Bytes(vec![0xDE, 0xAD, 0xBE, 0xEF]);
+        let s = to_string(&value).unwrap();
+        let back: DSFValue = from_str(&s).unwrap();
+        assert_eq!(back, value);
+    }
+
+    #[test]
+    fn serialize_bytes_produces_dsf_bytes_not_number_array() {
+        let value = ValueSerializer.serialize_bytes(&[0xDE, 0xAD]).unwrap();
+        assert_eq!(value, DSFValue::Bytes(vec![0xDE, 0xAD]));
+    }
+}
@@ -0,0 +1,423 @@
+//! Opt-in SIMD fast path for [`crate::dsf::parse`], gated behind the `simd`
+//! feature, modeled on simdjson's two-stage design:
+//!
+//! * **Stage 1** scans the validated UTF-8 input in 64-byte chunks, building
+//!   a bitmap of "structural" byte offsets (`{ } [ ] : ,` and the quotes that
+//!   open/close strings), masking out any of those bytes that fall inside a
+//!   quoted string so escaped characters don't produce false positives.
+//! * **Stage 2** walks only the structural offsets to build the `DSFValue`
+//!   tree, skipping the byte-by-byte whitespace/scan loop the scalar
+//!   [`crate::dsf::DSFParser`] uses.
+//!
+//! Non-x86_64 targets (and x86_64 without AVX2/PCLMULQDQ at runtime) fall
+//! back to the scalar structural scan below; `parse_simd`'s output is
+//! identical to [`crate::dsf::parse`] either way.
+use crate::dsf::{DSFError, DSFValue};
+use std::collections::HashMap;
+
+const CHUNK: usize = 64;
+
+/// A structural byte: the start of a token the stage-2 walker needs to stop
+/// at. Whitespace and string interiors are never structural.
+#[inline]
+fn is_structural(b: u8) -> bool {
+    matches!(b, b'{' | b'}' | b'[' | b']' | b':' | b',' | b'"')
+}
+
+/// Stage 1: build the sorted list of structural byte offsets in `input`,
+/// with offsets inside quoted strings (other than the quotes themselves)
+/// excluded.
+///
+/// Dispatches to an AVX2+PCLMULQDQ chunked implementation when the running
+/// CPU supports it, and a scalar fallback otherwise. Both produce the same
+/// offsets; the scalar path is simply not vectorized.
+fn build_structural_indices(input: &[u8]) -> Vec<usize> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("pclmulqdq") {
+            // SAFETY: both required features were just checked at runtime.
+            return unsafe { build_structural_indices_avx2(input) };
+        }
+    }
+    build_structural_indices_scalar(input)
+}
+
+fn build_structural_indices_scalar(input: &[u8]) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(input.len() / 4);
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &b) in input.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+                indices.push(i);
+            }
+            continue;
+        }
+        if b == b'"' {
+            in_string = true;
+            indices.push(i);
+        } else if is_structural(b) {
+            indices.push(i);
+        }
+    }
+    indices
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,pclmulqdq")]
+unsafe fn build_structural_indices_avx2(input: &[u8]) -> Vec<usize> {
+    use std::arch::x86_64::*;
+
+    let mut indices = Vec::with_capacity(input.len() / 4);
+    let mut prev_in_string: u64 = 0; // carries the "currently inside a string" state across chunks
+    let mut prev_escape_run: u64 = 0; // carries an odd trailing run of backslashes across chunks
+
+    let quote = _mm256_set1_epi8(b'"' as i8);
+    let backslash = _mm256_set1_epi8(b'\\' as i8);
+    let brace_open = _mm256_set1_epi8(b'{' as i8);
+    let brace_close = _mm256_set1_epi8(b'}' as i8);
+    let bracket_open = _mm256_set1_epi8(b'[' as i8);
+    let bracket_close = _mm256_set1_epi8(b']' as i8);
+    let colon = _mm256_set1_epi8(b':' as i8);
+    let comma = _mm256_set1_epi8(b',' as i8);
+
+    let mut pos = 0;
+    while pos < input.len() {
+        let len = (input.len() - pos).min(CHUNK);
+        let mut buf = [0u8; CHUNK];
+        buf[..len].copy_from_slice(&input[pos..pos + len]);
+
+        let lo = _mm256_loadu_si256(buf[0..32].as_ptr() as *const __m256i);
+        let hi = _mm256_loadu_si256(buf[32..64].as_ptr() as *const __m256i);
+
+        let quote_mask = (_mm256_movemask_epi8(_mm256_cmpeq_epi8(lo, quote)) as u32 as u64)
+            | ((_mm256_movemask_epi8(_mm256_cmpeq_epi8(hi, quote)) as u32 as u64) << 32);
+        let backslash_mask = (_mm256_movemask_epi8(_mm256_cmpeq_epi8(lo, backslash)) as u32 as u64)
+            | ((_mm256_movemask_epi8(_mm256_cmpeq_epi8(hi, backslash)) as u32 as u64) << 32);
+        let structural_mask = {
+            let eq = |v: __m256i, pat: __m256i| _mm256_cmpeq_epi8(v, pat);
+            let lo_m = _mm256_or_si256(
+                _mm256_or_si256(eq(lo, brace_open), eq(lo, brace_close)),
+                _mm256_or_si256(
+                    _mm256_or_si256(eq(lo, bracket_open), eq(lo, bracket_close)),
+                    _mm256_or_si256(eq(lo, colon), eq(lo, comma)),
+                ),
+            );
+            let hi_m = _mm256_or_si256(
+                _mm256_or_si256(eq(hi, brace_open), eq(hi, brace_close)),
+                _mm256_or_si256(
+                    _mm256_or_si256(eq(hi, bracket_open), eq(hi, bracket_close)),
+                    _mm256_or_si256(eq(hi, colon), eq(hi, comma)),
+                ),
+            );
+            (_mm256_movemask_epi8(lo_m) as u32 as u64) | ((_mm256_movemask_epi8(hi_m) as u32 as u64) << 32)
+        };
+
+        // A backslash only escapes the byte after it if it starts a run of
+        // *odd* length (an even-length run is pairs of backslashes escaping
+        // each other). `local_backslash` clears any run-starting bit that is
+        // actually a continuation of an odd run carried over from the
+        // previous chunk; `follows_escape` then marks every byte that
+        // immediately follows an active backslash. Splitting backslash runs
+        // into those starting on even vs. odd bit positions and comparing
+        // against a carry-add lets us tell which runs are odd-length without
+        // a serial scan -- the same trick simdjson uses for `find_escaped`.
+        let local_backslash = backslash_mask & !prev_escape_run;
+        let follows_escape = (local_backslash << 1) | prev_escape_run;
+        const EVEN_BITS: u64 = 0x5555_5555_5555_5555;
+        let odd_sequence_starts = local_backslash & !EVEN_BITS & !follows_escape;
+        let (sequences_starting_on_even_bits, carry) = odd_sequence_starts.overflowing_add(local_backslash);
+        prev_escape_run = carry as u64;
+        let invert_mask = sequences_starting_on_even_bits << 1;
+        let escaped = (EVEN_BITS ^ invert_mask) & follows_escape;
+
+        let escaped_quotes = escaped & quote_mask;
+        let real_quotes = quote_mask & !escaped_quotes;
+
+        // Carry-less multiply by all-ones computes, per bit, the parity of
+        // the number of set bits at-or-before it -- i.e. a prefix XOR. Used
+        // here to toggle "inside string" state at each real quote; XOR in
+        // the carried state from the previous chunk so strings that
+        // straddle a chunk boundary stay masked correctly.
+        let ones = _mm_set1_epi64x(-1i64);
+        let clmul = |mask: u64| -> u64 {
+            let a = _mm_set_epi64x(0, mask as i64);
+            let r = _mm_clmulepi64_si128::<0>(a, ones);
+            _mm_cvtsi128_si64(r) as u64
+        };
+
+        let string_prefix = clmul(real_quotes) ^ prev_in_string.wrapping_neg();
+        prev_in_string = string_prefix >> 63;
+
+        let visible_structural = (structural_mask & !string_prefix) | real_quotes;
+
+        let mut bits = visible_structural;
+        while bits != 0 {
+            let bit = bits.trailing_zeros() as usize;
+            if pos + bit < input.len() {
+                indices.push(pos + bit);
+            }
+            bits &= bits - 1;
+        }
+
+        pos += CHUNK;
+    }
+
+    indices
+}
+
+/// Stage 2: walk the structural offsets produced by stage 1 to build a
+/// [`DSFValue`] tree, the same shape [`crate::dsf::parse`] returns.
+struct StructuralWalker<'a> {
+    input: &'a [u8],
+    structurals: &'a [usize],
+    cursor: usize,
+}
+
+impl<'a> StructuralWalker<'a> {
+    fn next_structural(&mut self) -> Option<usize> {
+        let idx = self.structurals.get(self.cursor).copied();
+        if idx.is_some() {
+            self.cursor += 1;
+        }
+        idx
+    }
+
+    fn peek_structural(&self) -> Option<usize> {
+        self.structurals.get(self.cursor).copied()
+    }
+
+    fn parse_value(&mut self, start: usize) -> Result<(DSFValue, usize), DSFError> {
+        let start = skip_ws(self.input, start);
+        match self.input.get(start) {
+            Some(b'{') => self.parse_object(start),
+            Some(b'[') => self.parse_array(start),
+            Some(b'"') => {
+                let (s, end) = self.parse_string(start)?;
+                Ok((crate::dsf::detag_string(s), end))
+            }
+            Some(b't') | Some(b'f') | Some(b'n') | Some(b'-') | Some(b'0'..=b'9') => {
+                self.parse_scalar(start)
+            }
+            Some(&ch) => Err(DSFError::UnexpectedChar(start, ch as char)),
+            None => Err(DSFError::UnexpectedEOF),
+        }
+    }
+
+    fn parse_scalar(&mut self, start: usize) -> Result<(DSFValue, usize), DSFError> {
+        // Scalars (numbers and keywords) are never structural themselves, so
+        // they end exactly where the next structural byte begins.
+        let end = self
+            .peek_structural()
+            .filter(|&s| s > start)
+            .unwrap_or(self.input.len());
+        let raw = std::str::from_utf8(&self.input[start..end])
+            .map_err(|_| DSFError::InvalidNumber("invalid utf8".to_string()))?
+            .trim_end();
+        let value = match raw {
+            "true" => DSFValue::Bool(true),
+            "false" => DSFValue::Bool(false),
+            "null" => DSFValue::Null,
+            _ => raw
+                .parse::<f64>()
+                .map(DSFValue::Number)
+                .map_err(|_| DSFError::InvalidNumber(raw.to_string()))?,
+        };
+        Ok((value, start + raw.len()))
+    }
+
+    fn parse_string(&mut self, start: usize) -> Result<(String, usize), DSFError> {
+        // start points at the opening quote, which stage 1 already recorded
+        // as structural; the matching close is the next structural quote.
+        let open = self.next_structural().filter(|&s| s == start);
+        if open.is_none() {
+            return Err(DSFError::UnexpectedChar(start, '"'));
+        }
+        let content_start = start + 1;
+        let close = loop {
+            match self.next_structural() {
+                Some(idx) if self.input[idx] == b'"' => break idx,
+                Some(_) => continue,
+                None => return Err(DSFError::UnexpectedEOF),
+            }
+        };
+        let raw = std::str::from_utf8(&self.input[content_start..close])
+            .map_err(|_| DSFError::InvalidNumber("invalid utf8".to_string()))?;
+        Ok((unescape(raw), close + 1))
+    }
+
+    fn parse_object(&mut self, start: usize) -> Result<(DSFValue, usize), DSFError> {
+        self.next_structural(); // consume '{'
+        let mut map = HashMap::new();
+        let mut pos = start + 1;
+        loop {
+            pos = skip_ws(self.input, pos);
+            if self.input.get(pos) == Some(&b'}') {
+                self.next_structural();
+                return Ok((DSFValue::Object(map), pos + 1));
+            }
+            let key_end = find_key_end(self.input, pos);
+            let key = String::from_utf8_lossy(&self.input[pos..key_end]).into_owned();
+            let colon = skip_ws(self.input, key_end);
+            self.next_structural(); // consume ':'
+            let (value, after) = self.parse_value(colon + 1)?;
+            map.insert(key, value);
+            pos = skip_ws(self.input, after);
+            if self.input.get(pos) == Some(&b',') {
+                self.next_structural();
+                pos += 1;
+            }
+        }
+    }
+
+    fn parse_array(&mut self, start: usize) -> Result<(DSFValue, usize), DSFError> {
+        self.next_structural(); // consume '['
+        let mut arr = Vec::new();
+        let mut pos = start + 1;
+        loop {
+            pos = skip_ws(self.input, pos);
+            if self.input.get(pos) == Some(&b']') {
+                self.next_structural();
+                return Ok((DSFValue::Array(arr), pos + 1));
+            }
+            let (value, after) = self.parse_value(pos)?;
+            arr.push(value);
+            pos = skip_ws(self.input, after);
+            if self.input.get(pos) == Some(&b',') {
+                self.next_structural();
+                pos += 1;
+            }
+        }
+    }
+}
+
+#[inline]
+fn skip_ws(input: &[u8], mut pos: usize) -> usize {
+    while matches!(input.get(pos), Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n')) {
+        pos += 1;
+    }
+    pos
+}
+
+#[inline]
+fn find_key_end(input: &[u8], start: usize) -> usize {
+    let mut i = start;
+    while matches!(
+        input.get(i),
+        Some(b'a'..=b'z') | Some(b'A'..=b'Z') | Some(b'0'..=b'9') | Some(b'_')
+    ) {
+        i += 1;
+    }
+    i
+}
+
+fn unescape(raw: &str) -> String {
+    if !raw.contains('\\') {
+        return raw.to_string();
+    }
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('"') => out.push('"'),
+                Some('\\') => out.push('\\'),
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('r') => out.push('\r'),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// SIMD-accelerated equivalent of [`crate::dsf::parse`]. Assumes `input` is
+/// already-validated UTF-8 (the structural scan only reasons about ASCII
+/// punctuation, so invalid UTF-8 in between is passed through opaquely) --
+/// callers that can't already guarantee this should validate with
+/// `std::str::from_utf8` up front, which this function's `&str` signature
+/// already does for them.
+pub fn parse(input: &str) -> Result<DSFValue, DSFError> {
+    let bytes = input.as_bytes();
+    let structurals = build_structural_indices(bytes);
+    let mut walker = StructuralWalker {
+        input: bytes,
+        structurals: &structurals,
+        cursor: 0,
+    };
+    let first = skip_ws(bytes, 0);
+    let (value, end) = walker.parse_value(first)?;
+    let trailing = skip_ws(bytes, end);
+    if trailing < bytes.len() {
+        return Err(DSFError::TrailingData(trailing));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `crate::dsf::parse` itself dispatches to `dsf_simd::parse` when the
+    // `simd` feature is on, so comparing against it here would compare the
+    // SIMD path against itself under `--features simd`. Go through
+    // `DSFParser` directly to pin down the scalar reference implementation.
+    fn scalar_parse(input: &str) -> DSFValue {
+        crate::dsf::DSFParser::new(input).parse().unwrap()
+    }
+
+    #[test]
+    fn matches_scalar_parser() {
+        let input = r#"{a: 1, b: [true, false, null], c: "hi there"}"#;
+        let scalar = scalar_parse(input);
+        let simd = parse(input).unwrap();
+        assert_eq!(scalar, simd);
+    }
+
+    #[test]
+    fn matches_scalar_parser_with_escapes() {
+        let input = r#"{msg: "a \"quoted\" word"}"#;
+        let scalar = scalar_parse(input);
+        let simd = parse(input).unwrap();
+        assert_eq!(scalar, simd);
+    }
+
+    #[test]
+    fn matches_scalar_parser_string_spanning_chunk_boundary() {
+        // CHUNK is 64 bytes; pad the quoted string well past 2 chunks so the
+        // closing quote lands in a later chunk, exercising the
+        // `prev_in_string` carry between AVX2 chunks instead of just
+        // within a single one.
+        let filler = "x".repeat(CHUNK * 2);
+        let input = format!(r#"{{a: "{}"}}"#, filler);
+        assert!(input.len() > CHUNK * 2);
+        let scalar = scalar_parse(&input);
+        let simd = parse(&input).unwrap();
+        assert_eq!(scalar, simd);
+    }
+
+    #[test]
+    fn matches_scalar_parser_escape_spanning_chunk_boundary() {
+        // Place the `\"` escape pair so the backslash is the last byte of
+        // the first chunk and the escaped quote is the first byte of the
+        // next, exercising `prev_escape_run` carried across the boundary.
+        let prefix = r#"{a: ""#;
+        let filler_len = CHUNK - 1 - prefix.len();
+        let filler = "x".repeat(filler_len);
+        let input = format!(r#"{}{}\"{}"}}"#, prefix, filler, "y".repeat(20));
+        assert_eq!(input.as_bytes()[CHUNK - 1], b'\\');
+        assert_eq!(input.as_bytes()[CHUNK], b'"');
+        let scalar = scalar_parse(&input);
+        let simd = parse(&input).unwrap();
+        assert_eq!(scalar, simd);
+    }
+}
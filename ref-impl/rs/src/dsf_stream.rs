@@ -0,0 +1,118 @@
+//! Streaming line-delimited DSF ("ND-DSF"), analogous to newline-delimited
+//! JSON: each line of the stream is one independent DSF document. This lets
+//! callers append entries to a file and process multi-gigabyte logs
+//! record-by-record instead of materializing one giant root value.
+use std::io::{self, BufRead, Write};
+
+use crate::dsf::{self, DSFError, DSFValue};
+
+/// Reads an ND-DSF stream one line/document at a time.
+///
+/// ```ignore
+/// let reader = DsfStreamReader::new(std::io::BufReader::new(file));
+/// for entry in reader {
+///     let value = entry?;
+///     // ...
+/// }
+/// ```
+pub struct DsfStreamReader<R: BufRead> {
+    reader: R,
+    line: String,
+}
+
+impl<R: BufRead> DsfStreamReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for DsfStreamReader<R> {
+    type Item = Result<DSFValue, DSFError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {
+                    let trimmed = self.line.trim();
+                    if trimmed.is_empty() {
+                        continue;
+                    }
+                    return Some(dsf::parse(trimmed));
+                }
+                Err(e) => return Some(Err(DSFError::InvalidNumber(format!("io error: {}", e)))),
+            }
+        }
+    }
+}
+
+/// Writes DSF values to an ND-DSF stream, one compact (unindented) document
+/// per line.
+pub struct DsfStreamWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> DsfStreamWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Append a single value as its own line.
+    pub fn write_value(&mut self, value: &DSFValue) -> io::Result<()> {
+        let line = dsf::stringify(value, None);
+        debug_assert!(!line.contains('\n'), "compact stringify must not emit newlines");
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::BufReader;
+
+    #[test]
+    fn round_trips_multiple_entries() {
+        let mut buf = Vec::new();
+        {
+            let mut writer = DsfStreamWriter::new(&mut buf);
+            writer
+                .write_value(&DSFValue::Object(HashMap::from([(
+                    "id".to_string(),
+                    DSFValue::Number(1.0),
+                )])))
+                .unwrap();
+            writer
+                .write_value(&DSFValue::Object(HashMap::from([(
+                    "id".to_string(),
+                    DSFValue::Number(2.0),
+                )])))
+                .unwrap();
+        }
+
+        let reader = DsfStreamReader::new(BufReader::new(buf.as_slice()));
+        let entries: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn skips_blank_lines() {
+        let input = "{a: 1}\n\n{a: 2}\n";
+        let reader = DsfStreamReader::new(BufReader::new(input.as_bytes()));
+        let entries: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(entries.len(), 2);
+    }
+}
@@ -0,0 +1,517 @@
+//! Parse a single DTXT document incrementally from any [`std::io::Read`],
+//! for callers (multi-gigabyte logs, network streams) who don't want to
+//! buffer the whole document into one `String` up front the way
+//! [`crate::parse`] requires.
+//!
+//! The core [`crate::DTXTParser`] borrows `&'a str` slices straight out of
+//! its input, which only works because that input is one contiguous,
+//! already-in-memory slice. Here the buffer grows and compacts as more
+//! bytes arrive from the reader, so earlier slices would be invalidated
+//! out from under any borrow — [`OwnedValue`] mirrors [`crate::DTXTValue`]
+//! but owns every string and byte sequence instead.
+use std::collections::HashMap;
+use std::io::Read;
+
+use memchr::{memchr, memchr2};
+use num_bigint::BigInt;
+
+use crate::DTXTError;
+
+/// How many bytes to request from the reader per refill.
+const READ_CHUNK: usize = 8192;
+
+/// An owned counterpart to [`crate::DTXTValue`], used by [`parse_reader`]
+/// since streamed input can't support the core parser's zero-copy borrows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    /// Arbitrary-precision, matching [`crate::DTXTValue::BigInt`].
+    BigInt(BigInt),
+    Bytes(Vec<u8>),
+    Date(String),
+    Array(Vec<OwnedValue>),
+    Object(HashMap<String, OwnedValue>),
+}
+
+/// Buffers bytes from a `Read` on demand, compacting already-consumed bytes
+/// out of the front so memory use tracks the largest single token rather
+/// than the whole stream, and tracking the absolute offset of the cursor
+/// across refills for error reporting.
+struct Source<R: Read> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Index into `buf` of the next unread byte.
+    cursor: usize,
+    /// Absolute stream offset of `buf[0]`.
+    base: usize,
+    eof: bool,
+}
+
+impl<R: Read> Source<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::with_capacity(READ_CHUNK),
+            cursor: 0,
+            base: 0,
+            eof: false,
+        }
+    }
+
+    /// The parser's current absolute byte offset, used to locate an error.
+    fn pos(&self) -> usize {
+        self.base + self.cursor
+    }
+
+    /// Drop already-consumed bytes from the front of the buffer so it
+    /// doesn't grow without bound across a long stream.
+    fn compact(&mut self) {
+        if self.cursor > 0 {
+            self.buf.drain(..self.cursor);
+            self.base += self.cursor;
+            self.cursor = 0;
+        }
+    }
+
+    /// Pull another chunk from the reader, growing the buffer. Returns
+    /// `false` once the reader is exhausted.
+    fn fill(&mut self) -> Result<bool, DTXTError> {
+        if self.eof {
+            return Ok(false);
+        }
+        self.compact();
+        let start = self.buf.len();
+        self.buf.resize(start + READ_CHUNK, 0);
+        let n = self
+            .reader
+            .read(&mut self.buf[start..])
+            .map_err(|e| DTXTError::InvalidNumber(format!("io error: {}", e)))?;
+        self.buf.truncate(start + n);
+        if n == 0 {
+            self.eof = true;
+        }
+        Ok(n > 0)
+    }
+
+    /// Make sure at least `len` bytes are available starting at the cursor,
+    /// refilling as needed. Having fewer than `len` available afterwards
+    /// just means the stream hit EOF first.
+    fn ensure(&mut self, len: usize) -> Result<(), DTXTError> {
+        while self.buf.len() - self.cursor < len {
+            if !self.fill()? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn current(&mut self) -> Result<Option<u8>, DTXTError> {
+        self.ensure(1)?;
+        Ok(self.buf.get(self.cursor).copied())
+    }
+
+    fn peek_next(&mut self) -> Result<Option<u8>, DTXTError> {
+        self.ensure(2)?;
+        Ok(self.buf.get(self.cursor + 1).copied())
+    }
+
+    fn advance(&mut self) {
+        self.cursor += 1;
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.cursor += n;
+    }
+
+    /// The relative offset (from the cursor) of the next `needle` byte,
+    /// growing the buffer as needed. `None` means the stream ended first.
+    fn find_from_cursor(&mut self, needle: u8) -> Result<Option<usize>, DTXTError> {
+        let mut scanned = 0;
+        loop {
+            if let Some(rel) = memchr(needle, &self.buf[self.cursor + scanned..]) {
+                return Ok(Some(scanned + rel));
+            }
+            scanned = self.buf.len() - self.cursor;
+            if !self.fill()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Like [`Self::find_from_cursor`] but for either of two needle bytes;
+    /// also reports which one matched.
+    fn find2_from_cursor(&mut self, a: u8, b: u8) -> Result<Option<(usize, u8)>, DTXTError> {
+        let mut scanned = 0;
+        loop {
+            if let Some(rel) = memchr2(a, b, &self.buf[self.cursor + scanned..]) {
+                return Ok(Some((scanned + rel, self.buf[self.cursor + scanned + rel])));
+            }
+            scanned = self.buf.len() - self.cursor;
+            if !self.fill()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Take the next `len` bytes (already guaranteed available by a prior
+    /// `find`) as an owned `String` and advance past them. `a` and `b` are
+    /// never split out of a multi-byte UTF-8 sequence since both are ASCII,
+    /// so this slice is always well-formed given valid UTF-8 input.
+    fn take_string(&mut self, len: usize) -> Result<String, DTXTError> {
+        let bytes = &self.buf[self.cursor..self.cursor + len];
+        let s = std::str::from_utf8(bytes)
+            .map_err(|_| DTXTError::InvalidNumber("invalid utf8".to_string()))?
+            .to_string();
+        self.cursor += len;
+        Ok(s)
+    }
+}
+
+/// Reads a DTXT document from a `Read`, refilling its internal buffer on
+/// demand instead of requiring the whole document up front.
+struct ReaderParser<R: Read> {
+    source: Source<R>,
+}
+
+impl<R: Read> ReaderParser<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            source: Source::new(reader),
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), DTXTError> {
+        loop {
+            match self.source.current()? {
+                Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') => self.source.advance(),
+                Some(b'/') if self.source.peek_next()? == Some(b'/') => {
+                    self.source.advance();
+                    self.source.advance();
+                    match self.source.find_from_cursor(b'\n')? {
+                        Some(rel) => self.source.skip(rel + 1),
+                        None => self.source.skip(self.source.buf.len() - self.source.cursor),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Scan an identifier (object key or constructor name): ASCII
+    /// alphanumerics and underscores, same alphabet as [`crate::DTXTParser`].
+    fn read_ident(&mut self) -> Result<String, DTXTError> {
+        let mut ident = String::new();
+        while let Some(ch) = self.source.current()? {
+            if ch.is_ascii_alphanumeric() || ch == b'_' {
+                ident.push(ch as char);
+                self.source.advance();
+            } else {
+                break;
+            }
+        }
+        Ok(ident)
+    }
+
+    pub fn parse(&mut self) -> Result<HashMap<String, OwnedValue>, DTXTError> {
+        self.skip_whitespace()?;
+        let result = self.parse_object()?;
+        self.skip_whitespace()?;
+        if self.source.current()?.is_some() {
+            return Err(DTXTError::TrailingData(self.source.pos()));
+        }
+        Ok(result)
+    }
+
+    fn parse_value(&mut self) -> Result<OwnedValue, DTXTError> {
+        self.skip_whitespace()?;
+        match self.source.current()? {
+            Some(b'{') => Ok(OwnedValue::Object(self.parse_object()?)),
+            Some(b'[') => Ok(OwnedValue::Array(self.parse_array()?)),
+            Some(b'`') => Ok(OwnedValue::String(self.parse_string()?)),
+            Some(b'-') | Some(b'0'..=b'9') => Ok(OwnedValue::Number(self.parse_number()?)),
+            Some(b'T') if self.source.peek_next()? != Some(b'(') => {
+                self.source.advance();
+                Ok(OwnedValue::Bool(true))
+            }
+            Some(b'F') if self.source.peek_next()? != Some(b'(') => {
+                self.source.advance();
+                Ok(OwnedValue::Bool(false))
+            }
+            Some(b'N') if self.source.peek_next()? != Some(b'(') => {
+                self.source.advance();
+                Ok(OwnedValue::Null)
+            }
+            Some(b'A'..=b'Z') | Some(b'a'..=b'z') | Some(b'_') => self.parse_constructor(),
+            Some(ch) => Err(DTXTError::UnexpectedChar(self.source.pos(), ch as char)),
+            None => Err(DTXTError::UnexpectedEOF),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<HashMap<String, OwnedValue>, DTXTError> {
+        self.source.advance(); // skip '{'
+        let mut map = HashMap::new();
+
+        self.skip_whitespace()?;
+        while self.source.current()? != Some(b'}') {
+            let key = self.read_ident()?;
+            self.skip_whitespace()?;
+
+            if self.source.current()? != Some(b':') {
+                return Err(DTXTError::UnexpectedChar(
+                    self.source.pos(),
+                    self.source.current()?.map(|c| c as char).unwrap_or('\0'),
+                ));
+            }
+            self.source.advance(); // skip ':'
+
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_whitespace()?;
+            if self.source.current()? == Some(b',') {
+                self.source.advance();
+                self.skip_whitespace()?;
+            }
+        }
+
+        self.source.advance(); // skip '}'
+        Ok(map)
+    }
+
+    fn parse_array(&mut self) -> Result<Vec<OwnedValue>, DTXTError> {
+        self.source.advance(); // skip '['
+        let mut arr = Vec::new();
+
+        self.skip_whitespace()?;
+        while self.source.current()? != Some(b']') {
+            arr.push(self.parse_value()?);
+
+            self.skip_whitespace()?;
+            if self.source.current()? == Some(b',') {
+                self.source.advance();
+                self.skip_whitespace()?;
+            }
+        }
+
+        self.source.advance(); // skip ']'
+        Ok(arr)
+    }
+
+    /// Scan a backtick string, handling the same `\`-escapes as
+    /// [`crate::DTXTParser::parse_string`] (there's no borrowed fast path
+    /// here, since the buffer isn't guaranteed to hold the whole string at
+    /// once).
+    fn parse_string(&mut self) -> Result<String, DTXTError> {
+        self.source.advance(); // skip opening '`'
+        let mut s = String::new();
+        loop {
+            let (rel, which) = self
+                .source
+                .find2_from_cursor(b'`', b'\\')?
+                .ok_or(DTXTError::UnexpectedEOF)?;
+            if rel > 0 {
+                let chunk = self.source.take_string(rel)?;
+                s.push_str(&chunk);
+            }
+            match which {
+                b'`' => {
+                    self.source.advance();
+                    return Ok(s);
+                }
+                b'\\' => {
+                    self.source.advance();
+                    match self.source.current()? {
+                        Some(b'`') => {
+                            s.push('`');
+                            self.source.advance();
+                        }
+                        Some(b'\\') => {
+                            s.push('\\');
+                            self.source.advance();
+                        }
+                        Some(b'n') => {
+                            s.push('\n');
+                            self.source.advance();
+                        }
+                        Some(b't') => {
+                            s.push('\t');
+                            self.source.advance();
+                        }
+                        Some(b'r') => {
+                            s.push('\r');
+                            self.source.advance();
+                        }
+                        Some(b'u') => {
+                            self.source.advance(); // skip 'u'
+                            if self.source.current()? != Some(b'{') {
+                                return Err(DTXTError::UnexpectedChar(
+                                    self.source.pos(),
+                                    self.source.current()?.map(|c| c as char).unwrap_or('\0'),
+                                ));
+                            }
+                            self.source.advance(); // skip '{'
+                            let rel = self
+                                .source
+                                .find_from_cursor(b'}')?
+                                .ok_or(DTXTError::UnexpectedEOF)?;
+                            let hex = self.source.take_string(rel)?;
+                            let code = u32::from_str_radix(&hex, 16)
+                                .map_err(|_| DTXTError::InvalidEscape(format!("\\u{{{}}}", hex)))?;
+                            let ch = char::from_u32(code)
+                                .ok_or_else(|| DTXTError::InvalidEscape(format!("\\u{{{}}}", hex)))?;
+                            s.push(ch);
+                            self.source.advance(); // skip '}'
+                        }
+                        Some(ch) => return Err(DTXTError::UnexpectedChar(self.source.pos(), ch as char)),
+                        None => return Err(DTXTError::UnexpectedEOF),
+                    }
+                }
+                _ => unreachable!("find2_from_cursor only matches '`' or '\\\\'"),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<f64, DTXTError> {
+        let mut s = String::new();
+        if self.source.current()? == Some(b'-') {
+            s.push('-');
+            self.source.advance();
+        }
+        if self.source.current()? == Some(b'0') {
+            s.push('0');
+            self.source.advance();
+        } else if matches!(self.source.current()?, Some(b'1'..=b'9')) {
+            while let Some(b @ b'0'..=b'9') = self.source.current()? {
+                s.push(b as char);
+                self.source.advance();
+            }
+        }
+        if self.source.current()? == Some(b'.') {
+            s.push('.');
+            self.source.advance();
+            while let Some(b @ b'0'..=b'9') = self.source.current()? {
+                s.push(b as char);
+                self.source.advance();
+            }
+        }
+        if matches!(self.source.current()?, Some(b'e') | Some(b'E')) {
+            s.push(self.source.current()?.unwrap() as char);
+            self.source.advance();
+            if matches!(self.source.current()?, Some(b'+') | Some(b'-')) {
+                s.push(self.source.current()?.unwrap() as char);
+                self.source.advance();
+            }
+            while let Some(b @ b'0'..=b'9') = self.source.current()? {
+                s.push(b as char);
+                self.source.advance();
+            }
+        }
+        s.parse::<f64>().map_err(|_| DTXTError::InvalidNumber(s))
+    }
+
+    fn parse_constructor(&mut self) -> Result<OwnedValue, DTXTError> {
+        let type_name = self.read_ident()?;
+
+        if self.source.current()? != Some(b'(') {
+            return Err(DTXTError::InvalidConstructor(type_name));
+        }
+        self.source.advance(); // skip '('
+
+        let rel = self
+            .source
+            .find_from_cursor(b')')?
+            .ok_or(DTXTError::UnexpectedEOF)?;
+        let payload = self.source.take_string(rel)?;
+        self.source.advance(); // skip ')'
+
+        match type_name.as_str() {
+            "D" => Ok(OwnedValue::Date(payload)),
+            "BN" => {
+                let num = match payload.parse::<i64>() {
+                    Ok(n) => BigInt::from(n),
+                    Err(_) => payload
+                        .parse::<BigInt>()
+                        .map_err(|_| DTXTError::InvalidConstructor(format!("BN({})", payload)))?,
+                };
+                Ok(OwnedValue::BigInt(num))
+            }
+            "B" => {
+                let mut bytes = Vec::with_capacity(payload.len() / 2);
+                for i in (0..payload.len()).step_by(2) {
+                    let byte = u8::from_str_radix(&payload[i..i + 2], 16)
+                        .map_err(|_| DTXTError::InvalidConstructor(format!("B({})", payload)))?;
+                    bytes.push(byte);
+                }
+                Ok(OwnedValue::Bytes(bytes))
+            }
+            _ => Err(DTXTError::InvalidConstructor(type_name)),
+        }
+    }
+}
+
+/// Parse a DTXT document incrementally from any `Read`, without requiring
+/// the caller to buffer it into a `String` first.
+pub fn parse_reader<R: Read>(reader: R) -> Result<HashMap<String, OwnedValue>, DTXTError> {
+    ReaderParser::new(reader).parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_object() {
+        let input = b"{name: `John`, age: 30}" as &[u8];
+        let result = parse_reader(input).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.get("name"), Some(&OwnedValue::String("John".to_string())));
+    }
+
+    #[test]
+    fn test_straddles_tiny_reads() {
+        // A reader that only ever hands back one byte at a time forces
+        // every scan in the parser to refill repeatedly mid-token.
+        struct OneByteAtATime<'a>(&'a [u8]);
+        impl<'a> Read for OneByteAtATime<'a> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0.is_empty() || buf.is_empty() {
+                    return Ok(0);
+                }
+                buf[0] = self.0[0];
+                self.0 = &self.0[1..];
+                Ok(1)
+            }
+        }
+
+        let input = b"{greeting: `hi \\`there\\``, items: [1, 2, 3], big: BN(123456789012345)}";
+        let result = parse_reader(OneByteAtATime(input)).unwrap();
+        assert_eq!(
+            result.get("greeting"),
+            Some(&OwnedValue::String("hi `there`".to_string()))
+        );
+        match result.get("items") {
+            Some(OwnedValue::Array(arr)) => assert_eq!(arr.len(), 3),
+            other => panic!("expected array, got {:?}", other),
+        }
+        assert_eq!(
+            result.get("big"),
+            Some(&OwnedValue::BigInt(BigInt::from(123456789012345i64)))
+        );
+    }
+
+    #[test]
+    fn test_comment_running_to_eof_without_trailing_newline() {
+        let input = b"{a: 1} // trailing comment, no newline" as &[u8];
+        let result = parse_reader(input).unwrap();
+        assert_eq!(result.get("a"), Some(&OwnedValue::Number(1.0)));
+    }
+
+    #[test]
+    fn test_rejects_trailing_data() {
+        let input = b"{a: 1} garbage" as &[u8];
+        assert!(matches!(parse_reader(input), Err(DTXTError::TrailingData(_))));
+    }
+}
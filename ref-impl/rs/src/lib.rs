@@ -1,9 +1,24 @@
+use std::borrow::Cow;
 use std::fmt;
+use num_bigint::BigInt;
 use rustc_hash::FxHashMap;
-use memchr::memchr;
+use memchr::{memchr, memchr2};
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList, PyString};
+use pyo3::types::{PyBool, PyByteArray, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
 use pyo3::IntoPyObjectExt;
+use std::collections::HashSet;
+
+pub mod dsf;
+pub mod dsf_schema;
+pub mod dsf_stream;
+pub mod dtxt_stream;
+pub mod packed;
+#[cfg(feature = "simd")]
+pub mod dsf_simd;
+#[cfg(feature = "serde")]
+pub mod dsf_serde;
+#[cfg(feature = "serde")]
+pub use dsf_serde::{from_str, to_string};
 
 #[derive(Debug)]
 pub enum DTXTError {
@@ -12,6 +27,9 @@ pub enum DTXTError {
     InvalidNumber(String),
     InvalidConstructor(String),
     TrailingData(usize),
+    /// A malformed `\`-escape inside a backtick string (bad `\u{...}` hex,
+    /// or a codepoint that isn't a valid `char` — e.g. a surrogate).
+    InvalidEscape(String),
 }
 
 impl fmt::Display for DTXTError {
@@ -22,25 +40,140 @@ impl fmt::Display for DTXTError {
             DTXTError::InvalidNumber(s) => write!(f, "Invalid number: {}", s),
             DTXTError::InvalidConstructor(s) => write!(f, "Invalid constructor: {}", s),
             DTXTError::TrailingData(pos) => write!(f, "Trailing data at position {}", pos),
+            DTXTError::InvalidEscape(s) => write!(f, "Invalid escape sequence: {}", s),
         }
     }
 }
 
 impl std::error::Error for DTXTError {}
 
+/// A 1-based line/column location in a source document, computed lazily
+/// from a byte offset only once an error actually bubbles out — the parser
+/// itself stays in cheap byte offsets on the happy path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Position {
+    fn from_offset(input: &str, offset: usize) -> Position {
+        let mut line = 1u32;
+        let mut column = 1u32;
+        for &b in input.as_bytes().iter().take(offset) {
+            if b == b'\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        Position { line, column }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A [`DTXTError`] paired with the line/column it occurred at, returned by
+/// the public [`parse`] so users debugging a multi-kilobyte config file get
+/// a location they can jump to instead of a raw byte offset.
+#[derive(Debug)]
+pub struct SpannedError {
+    pub code: DTXTError,
+    pub position: Position,
+}
+
+impl fmt::Display for SpannedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match &self.code {
+            DTXTError::UnexpectedChar(_, ch) => format!("unexpected char '{}'", ch),
+            DTXTError::UnexpectedEOF => "unexpected end of file".to_string(),
+            DTXTError::InvalidNumber(s) => format!("invalid number '{}'", s),
+            DTXTError::InvalidConstructor(s) => format!("invalid constructor '{}'", s),
+            DTXTError::TrailingData(_) => "trailing data".to_string(),
+            DTXTError::InvalidEscape(s) => format!("invalid escape sequence '{}'", s),
+        };
+        write!(f, "{} at {}", message, self.position)
+    }
+}
+
+impl std::error::Error for SpannedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.code)
+    }
+}
+
+/// The byte offset a [`DTXTError`] occurred at, where the variant carries
+/// one explicitly; otherwise `fallback` (the parser's position when the
+/// error was returned) is used.
+fn error_offset(err: &DTXTError, fallback: usize) -> usize {
+    match err {
+        DTXTError::UnexpectedChar(pos, _) => *pos,
+        DTXTError::TrailingData(pos) => *pos,
+        DTXTError::UnexpectedEOF
+        | DTXTError::InvalidNumber(_)
+        | DTXTError::InvalidConstructor(_)
+        | DTXTError::InvalidEscape(_) => fallback,
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DTXTValue<'a> {
-    String(&'a str),
+    /// Borrowed straight out of the source when the backtick string has no
+    /// `\`-escapes (the common case); owned only when unescaping allocated.
+    String(Cow<'a, str>),
     Number(f64),
     Bool(bool),
     Null,
-    BigInt(i64),
+    /// Arbitrary-precision integer carried by the `BN(...)` constructor, so
+    /// values beyond `i64` round-trip losslessly instead of being rejected.
+    BigInt(BigInt),
     Date(&'a str),
     Bytes(Vec<u8>),
     Array(Vec<DTXTValue<'a>>),
     Object(FxHashMap<&'a str, DTXTValue<'a>>),
 }
 
+const IDENT_OTHER: u8 = 1 << 0;
+const DIGIT: u8 = 1 << 1;
+const WHITESPACE: u8 = 1 << 2;
+
+const fn build_class_table() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0usize;
+    while i < 256 {
+        let b = i as u8;
+        let mut flags = 0u8;
+        if (b >= b'a' && b <= b'z') || (b >= b'A' && b <= b'Z') || b == b'_' || b.is_ascii_digit() {
+            flags |= IDENT_OTHER;
+        }
+        if b.is_ascii_digit() {
+            flags |= DIGIT;
+        }
+        if b == b' ' || b == b'\t' || b == b'\r' || b == b'\n' {
+            flags |= WHITESPACE;
+        }
+        table[i] = flags;
+        i += 1;
+    }
+    table
+}
+
+/// Precomputed byte classification used by the hot scanning loops
+/// (`skip_whitespace`, `parse_key`, `parse_number`, the `parse_value`
+/// dispatch) so each byte is classified with a single table lookup instead
+/// of a chain of `is_ascii_*`/`matches!` range checks.
+const CLASS: [u8; 256] = build_class_table();
+
+#[inline(always)]
+fn class(b: u8) -> u8 {
+    CLASS[b as usize]
+}
+
 pub struct DTXTParser<'a> {
     input: &'a [u8],
     pos: usize,
@@ -54,6 +187,12 @@ impl<'a> DTXTParser<'a> {
         }
     }
 
+    /// The parser's current byte offset, used to locate an error that
+    /// doesn't carry its own offset (see [`error_offset`]).
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
     #[inline]
     fn current(&self) -> Option<u8> {
         self.input.get(self.pos).copied()
@@ -69,10 +208,13 @@ impl<'a> DTXTParser<'a> {
         let mut i = self.pos;
         let bytes = self.input;
         let len = bytes.len();
-        
+
         while i < len {
+            if class(bytes[i]) & WHITESPACE != 0 {
+                i += 1;
+                continue;
+            }
             match bytes[i] {
-                b' ' | b'\t' | b'\r' | b'\n' => i += 1,
                 b'/' if i + 1 < len && bytes[i + 1] == b'/' => {
                     i += 2;
                     if let Some(next_nl) = memchr(b'\n', &bytes[i..]) {
@@ -109,7 +251,8 @@ impl<'a> DTXTParser<'a> {
             Some(b'{') => Ok(DTXTValue::Object(self.parse_object()?)),
             Some(b'[') => Ok(DTXTValue::Array(self.parse_array()?)),
             Some(b'`') => Ok(DTXTValue::String(self.parse_string()?)),
-            Some(b'-') | Some(b'0'..=b'9') => Ok(DTXTValue::Number(self.parse_number()?)),
+            Some(b'-') => Ok(DTXTValue::Number(self.parse_number()?)),
+            Some(ch) if class(ch) & DIGIT != 0 => Ok(DTXTValue::Number(self.parse_number()?)),
             Some(b'T') if self.peek_next() != Some(b'(') => {
                 self.advance();
                 Ok(DTXTValue::Bool(true))
@@ -181,29 +324,104 @@ impl<'a> DTXTParser<'a> {
         let bytes = self.input;
         let len = bytes.len();
         let mut i = start;
-        while i < len {
-            let ch = bytes[i];
-            if ch.is_ascii_alphanumeric() || ch == b'_' {
-                i += 1;
-            } else {
-                break;
-            }
+        while i < len && class(bytes[i]) & IDENT_OTHER != 0 {
+            i += 1;
         }
         self.pos = i;
         // Unsafe because we assume the input is valid UTF-8 (as per spec) and we only parsed ASCII
         unsafe { Ok(std::str::from_utf8_unchecked(&bytes[start..i])) }
     }
 
-    fn parse_string(&mut self) -> Result<&'a str, DTXTError> {
+    /// Scan a backtick string. The common case has no `\`-escape before the
+    /// closing backtick, so a single `memchr2` tells us whether we can hand
+    /// back a borrowed slice or need to fall through to the escape-aware,
+    /// allocating scan in [`Self::parse_string_escaped`].
+    fn parse_string(&mut self) -> Result<Cow<'a, str>, DTXTError> {
         self.advance(); // skip opening '`'
         let start = self.pos;
-        if let Some(end) = memchr(b'`', &self.input[start..]) {
-            let abs_end = start + end;
-            self.pos = abs_end + 1;
-            // Unsafe because we already validated the presence of closing '`' and assume valid UTF-8 input
-            unsafe { Ok(std::str::from_utf8_unchecked(&self.input[start..abs_end])) }
-        } else {
-            Err(DTXTError::UnexpectedEOF)
+        match memchr2(b'`', b'\\', &self.input[start..]) {
+            Some(rel) if self.input[start + rel] == b'`' => {
+                let abs_end = start + rel;
+                self.pos = abs_end + 1;
+                // Unsafe because we already validated the presence of closing '`' and assume valid UTF-8 input
+                unsafe { Ok(Cow::Borrowed(std::str::from_utf8_unchecked(&self.input[start..abs_end]))) }
+            }
+            Some(_) => self.parse_string_escaped(start),
+            None => Err(DTXTError::UnexpectedEOF),
+        }
+    }
+
+    fn parse_string_escaped(&mut self, start: usize) -> Result<Cow<'a, str>, DTXTError> {
+        self.pos = start;
+        let mut s = String::new();
+        loop {
+            let rel = memchr2(b'`', b'\\', &self.input[self.pos..]).ok_or(DTXTError::UnexpectedEOF)?;
+            if rel > 0 {
+                let chunk_start = self.pos;
+                self.pos += rel;
+                // Unsafe: same valid-UTF-8 assumption as the fast path above.
+                let chunk = unsafe { std::str::from_utf8_unchecked(&self.input[chunk_start..self.pos]) };
+                s.push_str(chunk);
+            }
+            match self.current() {
+                Some(b'`') => {
+                    self.advance();
+                    return Ok(Cow::Owned(s));
+                }
+                Some(b'\\') => {
+                    self.advance();
+                    match self.current() {
+                        Some(b'`') => {
+                            s.push('`');
+                            self.advance();
+                        }
+                        Some(b'\\') => {
+                            s.push('\\');
+                            self.advance();
+                        }
+                        Some(b'n') => {
+                            s.push('\n');
+                            self.advance();
+                        }
+                        Some(b't') => {
+                            s.push('\t');
+                            self.advance();
+                        }
+                        Some(b'r') => {
+                            s.push('\r');
+                            self.advance();
+                        }
+                        Some(b'u') => {
+                            self.advance(); // skip 'u'
+                            if self.current() != Some(b'{') {
+                                return Err(DTXTError::UnexpectedChar(
+                                    self.pos,
+                                    self.current().map(|c| c as char).unwrap_or('\0'),
+                                ));
+                            }
+                            self.advance(); // skip '{'
+                            let hex_start = self.pos;
+                            while !matches!(self.current(), Some(b'}') | None) {
+                                self.advance();
+                            }
+                            if self.current() != Some(b'}') {
+                                return Err(DTXTError::UnexpectedEOF);
+                            }
+                            let hex = std::str::from_utf8(&self.input[hex_start..self.pos])
+                                .map_err(|_| DTXTError::InvalidEscape("invalid utf8 in \\u{...}".to_string()))?;
+                            let code = u32::from_str_radix(hex, 16)
+                                .map_err(|_| DTXTError::InvalidEscape(format!("\\u{{{}}}", hex)))?;
+                            let ch = char::from_u32(code)
+                                .ok_or_else(|| DTXTError::InvalidEscape(format!("\\u{{{}}}", hex)))?;
+                            s.push(ch);
+                            self.advance(); // skip '}'
+                        }
+                        Some(ch) => return Err(DTXTError::UnexpectedChar(self.pos, ch as char)),
+                        None => return Err(DTXTError::UnexpectedEOF),
+                    }
+                }
+                _ => unreachable!("memchr2 only matches '`' or '\\\\'"),
+            }
         }
     }
 
@@ -213,16 +431,16 @@ impl<'a> DTXTParser<'a> {
         if self.current() == Some(b'0') {
             self.advance();
         } else if matches!(self.current(), Some(b'1'..=b'9')) {
-            while matches!(self.current(), Some(b'0'..=b'9')) { self.advance(); }
+            while self.current().is_some_and(|b| class(b) & DIGIT != 0) { self.advance(); }
         }
         if self.current() == Some(b'.') {
             self.advance();
-            while matches!(self.current(), Some(b'0'..=b'9')) { self.advance(); }
+            while self.current().is_some_and(|b| class(b) & DIGIT != 0) { self.advance(); }
         }
         if matches!(self.current(), Some(b'e') | Some(b'E')) {
             self.advance();
             if matches!(self.current(), Some(b'+') | Some(b'-')) { self.advance(); }
-            while matches!(self.current(), Some(b'0'..=b'9')) { self.advance(); }
+            while self.current().is_some_and(|b| class(b) & DIGIT != 0) { self.advance(); }
         }
         let num_str = std::str::from_utf8(&self.input[start..self.pos])
             .map_err(|_| DTXTError::InvalidNumber("invalid utf8".to_string()))?;
@@ -232,12 +450,8 @@ impl<'a> DTXTParser<'a> {
 
     fn parse_constructor(&mut self) -> Result<DTXTValue<'a>, DTXTError> {
         let start = self.pos;
-        while let Some(ch) = self.current() {
-            if ch.is_ascii_alphanumeric() || ch == b'_' {
-                self.advance();
-            } else {
-                break;
-            }
+        while self.current().is_some_and(|ch| class(ch) & IDENT_OTHER != 0) {
+            self.advance();
         }
         let type_name = std::str::from_utf8(&self.input[start..self.pos])
             .map_err(|_| DTXTError::InvalidConstructor("invalid utf8".to_string()))?;
@@ -261,8 +475,14 @@ impl<'a> DTXTParser<'a> {
         match type_name {
             "D" => Ok(DTXTValue::Date(payload)),
             "BN" => {
-                let num = payload.parse::<i64>()
-                    .map_err(|_| DTXTError::InvalidConstructor(format!("BN({})", payload)))?;
+                // Most BN(...) payloads fit in an i64; only fall through to
+                // the general arbitrary-precision parse when they don't.
+                let num = match payload.parse::<i64>() {
+                    Ok(n) => BigInt::from(n),
+                    Err(_) => payload
+                        .parse::<BigInt>()
+                        .map_err(|_| DTXTError::InvalidConstructor(format!("BN({})", payload)))?,
+                };
                 Ok(DTXTValue::BigInt(num))
             }
             "B" => {
@@ -290,7 +510,16 @@ fn stringify_value(value: &DTXTValue, out: &mut String, indent: Option<&str>, le
     match value {
         DTXTValue::String(s) => {
             out.push('`');
-            out.push_str(s);
+            for ch in s.chars() {
+                match ch {
+                    '`' => out.push_str("\\`"),
+                    '\\' => out.push_str("\\\\"),
+                    '\n' => out.push_str("\\n"),
+                    '\t' => out.push_str("\\t"),
+                    '\r' => out.push_str("\\r"),
+                    c => out.push(c),
+                }
+            }
             out.push('`');
         }
         DTXTValue::Number(n) => {
@@ -375,160 +604,458 @@ fn stringify_value(value: &DTXTValue, out: &mut String, indent: Option<&str>, le
 
 // Public API
 #[inline]
-pub fn parse<'a>(input: &'a str) -> Result<FxHashMap<&'a str, DTXTValue<'a>>, DTXTError> {
+pub fn parse<'a>(input: &'a str) -> Result<FxHashMap<&'a str, DTXTValue<'a>>, SpannedError> {
     let mut parser = DTXTParser::new(input);
-    parser.parse()
+    parser.parse().map_err(|code| {
+        let offset = error_offset(&code, parser.pos());
+        SpannedError {
+            position: Position::from_offset(input, offset),
+            code,
+        }
+    })
 }
 
-// --- Python Bindings (Single-pass Optimization) ---
+/// Build a `PyValueError` carrying the same structured location as
+/// [`SpannedError`], via `.lineno`/`.col` attributes, so Python callers get
+/// the same line/column a Rust caller would get from [`SpannedError`].
+fn py_value_error_at(py: Python<'_>, message: impl Into<String>, position: Position) -> PyErr {
+    let err = PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+        "{} at {}",
+        message.into(),
+        position
+    ));
+    let _ = err.value(py).setattr("lineno", position.line);
+    let _ = err.value(py).setattr("col", position.column);
+    err
+}
 
-struct PyDTXTParser<'py, 'a> {
-    py: Python<'py>,
-    input: &'a [u8],
-    pos: usize,
+// --- Python Bindings ---
+
+/// `loads` goes through the real [`parse`]/[`DTXTValue`] pipeline (rather
+/// than a separate hand-rolled text→`PyObject` walk) so it shares `parse`'s
+/// `D(...)`/`BN(...)`/`B(...)` constructor handling and backslash-escape
+/// unescaping instead of re-implementing both, and so `loads(dumps(obj))`
+/// round-trips everything `dumps` emits.
+#[pyfunction]
+fn loads(py: Python<'_>, input: &str) -> PyResult<PyObject> {
+    let map = parse(input).map_err(|e| py_value_error_at(py, e.code.to_string(), e.position))?;
+    Ok(dtxt_value_to_pyobject(py, &DTXTValue::Object(map))?.into())
 }
 
-impl<'py, 'a> PyDTXTParser<'py, 'a> {
-    fn new(py: Python<'py>, input: &'a str) -> Self {
-        Self { py, input: input.as_bytes(), pos: 0 }
-    }
+/// Largest magnitude integer that round-trips exactly through `f64`; Python
+/// ints beyond this are emitted as `BN(...)` instead of `Number` so large
+/// ids/counters don't silently lose precision.
+const F64_SAFE_INT: i64 = 1 << 53;
 
-    #[inline(always)]
-    fn current(&self) -> Option<u8> {
-        self.input.get(self.pos).copied()
+fn hex_upper(bytes: &[u8]) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push(HEX[(byte >> 4) as usize] as char);
+        out.push(HEX[(byte & 0x0F) as usize] as char);
     }
+    out
+}
 
-    #[inline(always)]
-    fn advance(&mut self) {
-        self.pos += 1;
+/// Single-pass Python object → DTXT text walker, the `dumps` counterpart to
+/// [`dtxt_value_to_pyobject`]'s value → object walk. Tracks container
+/// identities in `visited` to raise on cyclic references instead of
+/// overflowing the stack.
+fn serialize_pyobject(
+    obj: &Bound<'_, PyAny>,
+    datetime_cls: &Bound<'_, PyAny>,
+    out: &mut String,
+    indent: Option<&str>,
+    level: usize,
+    visited: &mut HashSet<usize>,
+) -> PyResult<()> {
+    if obj.is_none() {
+        out.push('N');
+        return Ok(());
+    }
+    if let Ok(b) = obj.downcast::<PyBool>() {
+        out.push(if b.is_true() { 'T' } else { 'F' });
+        return Ok(());
+    }
+    if let Ok(i) = obj.downcast::<PyInt>() {
+        match i.extract::<i64>() {
+            Ok(n) if n.abs() <= F64_SAFE_INT => {
+                let mut buf = ryu::Buffer::new();
+                out.push_str(buf.format(n as f64));
+            }
+            Ok(n) => {
+                out.push_str("BN(");
+                out.push_str(&n.to_string());
+                out.push(')');
+            }
+            Err(_) => {
+                // Bigger than i64: BN(...) carries arbitrary precision, so
+                // fall back to Python's own decimal rendering rather than
+                // rejecting the value outright.
+                out.push_str("BN(");
+                out.push_str(&i.str()?.extract::<String>()?);
+                out.push(')');
+            }
+        }
+        return Ok(());
     }
+    if let Ok(f) = obj.downcast::<PyFloat>() {
+        let mut buf = ryu::Buffer::new();
+        out.push_str(buf.format(f.value()));
+        return Ok(());
+    }
+    if let Ok(s) = obj.downcast::<PyString>() {
+        out.push('`');
+        for ch in s.extract::<String>()?.chars() {
+            match ch {
+                '`' => out.push_str("\\`"),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                '\r' => out.push_str("\\r"),
+                c => out.push(c),
+            }
+        }
+        out.push('`');
+        return Ok(());
+    }
+    if let Ok(b) = obj.downcast::<PyBytes>() {
+        out.push_str("B(");
+        out.push_str(&hex_upper(b.as_bytes()));
+        out.push(')');
+        return Ok(());
+    }
+    if let Ok(b) = obj.downcast::<PyByteArray>() {
+        out.push_str("B(");
+        out.push_str(&hex_upper(&b.to_vec()));
+        out.push(')');
+        return Ok(());
+    }
+    if obj.is_instance(datetime_cls)? {
+        let iso: String = obj.call_method0("isoformat")?.extract()?;
+        out.push_str("D(");
+        out.push_str(&iso);
+        out.push(')');
+        return Ok(());
+    }
+    if let Ok(list) = obj.downcast::<PyList>() {
+        let id = list.as_ptr() as usize;
+        if !visited.insert(id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "cyclic reference in dumps input",
+            ));
+        }
+        serialize_sequence(list.iter(), list.len(), datetime_cls, out, indent, level, visited)?;
+        visited.remove(&id);
+        return Ok(());
+    }
+    if let Ok(tuple) = obj.downcast::<PyTuple>() {
+        let id = tuple.as_ptr() as usize;
+        if !visited.insert(id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "cyclic reference in dumps input",
+            ));
+        }
+        serialize_sequence(tuple.iter(), tuple.len(), datetime_cls, out, indent, level, visited)?;
+        visited.remove(&id);
+        return Ok(());
+    }
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        let id = dict.as_ptr() as usize;
+        if !visited.insert(id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "cyclic reference in dumps input",
+            ));
+        }
+        let mut keys: Vec<String> = dict
+            .keys()
+            .into_iter()
+            .map(|k| k.extract::<String>())
+            .collect::<PyResult<_>>()?;
+        keys.sort_unstable();
 
-    #[inline(always)]
-    fn skip_whitespace(&mut self) {
-        let mut i = self.pos;
-        let bytes = self.input;
-        let len = bytes.len();
-        while i < len {
-            match bytes[i] {
-                b' ' | b'\t' | b'\r' | b'\n' => i += 1,
-                b'/' if i + 1 < len && bytes[i + 1] == b'/' => {
-                    i += 2;
-                    if let Some(next_nl) = memchr(b'\n', &bytes[i..]) {
-                        i += next_nl + 1;
-                    } else {
-                        i = len;
+        if dict.is_empty() {
+            out.push_str("{}");
+        } else {
+            out.push('{');
+            if let Some(ind) = indent {
+                out.push('\n');
+                for key in &keys {
+                    for _ in 0..=level {
+                        out.push_str(ind);
+                    }
+                    out.push_str(key);
+                    out.push_str(": ");
+                    let value = dict.get_item(key.as_str())?.ok_or_else(|| {
+                        PyErr::new::<pyo3::exceptions::PyValueError, _>("dict key vanished during dumps")
+                    })?;
+                    serialize_pyobject(&value, datetime_cls, out, indent, level + 1, visited)?;
+                    out.push_str(",\n");
+                }
+                for _ in 0..level {
+                    out.push_str(ind);
+                }
+            } else {
+                for (i, key) in keys.iter().enumerate() {
+                    out.push_str(key);
+                    out.push(':');
+                    let value = dict.get_item(key.as_str())?.ok_or_else(|| {
+                        PyErr::new::<pyo3::exceptions::PyValueError, _>("dict key vanished during dumps")
+                    })?;
+                    serialize_pyobject(&value, datetime_cls, out, indent, level + 1, visited)?;
+                    if i < keys.len() - 1 {
+                        out.push(',');
                     }
                 }
-                _ => break,
             }
+            out.push('}');
         }
-        self.pos = i;
+        visited.remove(&id);
+        return Ok(());
     }
+    Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+        "Unsupported type for dumps: {}",
+        obj.get_type().name()?
+    )))
+}
 
-    fn parse_value(&mut self) -> PyResult<Bound<'py, PyAny>> {
-        self.skip_whitespace();
-        match self.current() {
-            Some(b'{') => self.parse_object().map(|v| v.into_any()),
-            Some(b'[') => self.parse_array().map(|v| v.into_any()),
-            Some(b'`') => self.parse_string().map(|v| v.into_any()),
-            Some(b'-') | Some(b'0'..=b'9') => self.parse_number(),
-            Some(b'T') => { self.advance(); Ok(true.into_py_any(self.py)?.into_bound(self.py)) }
-            Some(b'F') => { self.advance(); Ok(false.into_py_any(self.py)?.into_bound(self.py)) }
-            Some(b'N') => { self.advance(); Ok(self.py.None().into_bound(self.py)) }
-            Some(ch) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unexpected char: {}", ch as char))),
-            None => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Unexpected EOF")),
+fn serialize_sequence<'py>(
+    items: impl Iterator<Item = Bound<'py, PyAny>>,
+    len: usize,
+    datetime_cls: &Bound<'py, PyAny>,
+    out: &mut String,
+    indent: Option<&str>,
+    level: usize,
+    visited: &mut HashSet<usize>,
+) -> PyResult<()> {
+    if len == 0 {
+        out.push_str("[]");
+        return Ok(());
+    }
+    out.push('[');
+    if let Some(ind) = indent {
+        out.push('\n');
+        for item in items {
+            for _ in 0..=level {
+                out.push_str(ind);
+            }
+            serialize_pyobject(&item, datetime_cls, out, indent, level + 1, visited)?;
+            out.push_str(",\n");
+        }
+        for _ in 0..level {
+            out.push_str(ind);
+        }
+    } else {
+        let mut i = 0;
+        for item in items {
+            serialize_pyobject(&item, datetime_cls, out, indent, level + 1, visited)?;
+            i += 1;
+            if i < len {
+                out.push(',');
+            }
         }
     }
+    out.push(']');
+    Ok(())
+}
 
-    fn parse_object(&mut self) -> PyResult<Bound<'py, PyDict>> {
-        self.advance(); // {
-        let dict = PyDict::new(self.py);
-        self.skip_whitespace();
-        while self.current() != Some(b'}') {
-            let key = self.parse_key()?;
-            self.skip_whitespace();
-            self.advance(); // :
-            let val = self.parse_value()?;
-            dict.set_item(key, val)?;
-            self.skip_whitespace();
-            if self.current() == Some(b',') { self.advance(); self.skip_whitespace(); }
+#[pyfunction]
+#[pyo3(signature = (obj, indent=None))]
+fn dumps(py: Python<'_>, obj: PyObject, indent: Option<&str>) -> PyResult<String> {
+    let datetime_cls = py.import("datetime")?.getattr("datetime")?;
+    let mut out = String::with_capacity(1024);
+    let mut visited = HashSet::new();
+    serialize_pyobject(obj.bind(py), &datetime_cls, &mut out, indent, 0, &mut visited)?;
+    Ok(out)
+}
+
+/// Convert a decoded [`DTXTValue`] into the equivalent Python object; shared
+/// by [`loads`] (decoding through [`parse`]) and `loadb` (decoding through
+/// [`packed::from_bytes`]).
+fn dtxt_value_to_pyobject<'py>(py: Python<'py>, value: &DTXTValue) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        DTXTValue::Null => Ok(py.None().into_bound(py)),
+        DTXTValue::Bool(b) => Ok(b.into_py_any(py)?.into_bound(py)),
+        DTXTValue::Number(n) => Ok(n.into_py_any(py)?.into_bound(py)),
+        DTXTValue::String(s) => Ok(PyString::new(py, s).into_any()),
+        DTXTValue::BigInt(n) => {
+            // `num_bigint::BigInt` has no direct PyO3 conversion; go through
+            // Python's own decimal-string int parsing to get an unbounded
+            // native `int` instead of truncating to i64/f64.
+            Ok(py.import("builtins")?.call_method1("int", (n.to_string(),))?.into_any())
+        }
+        DTXTValue::Date(s) => Ok(PyString::new(py, s).into_any()),
+        DTXTValue::Bytes(b) => Ok(PyBytes::new(py, b).into_any()),
+        DTXTValue::Array(arr) => {
+            let list = PyList::empty(py);
+            for item in arr {
+                list.append(dtxt_value_to_pyobject(py, item)?)?;
+            }
+            Ok(list.into_any())
+        }
+        DTXTValue::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, value) in map {
+                dict.set_item(key, dtxt_value_to_pyobject(py, value)?)?;
+            }
+            Ok(dict.into_any())
         }
-        self.advance(); // }
-        Ok(dict)
     }
+}
 
-    fn parse_array(&mut self) -> PyResult<Bound<'py, PyList>> {
-        self.advance(); // [
-        let list = PyList::empty(self.py);
-        self.skip_whitespace();
-        while self.current() != Some(b']') {
-            list.append(self.parse_value()?)?;
-            self.skip_whitespace();
-            if self.current() == Some(b',') { self.advance(); self.skip_whitespace(); }
+#[pyfunction]
+fn loadb(py: Python<'_>, input: &[u8]) -> PyResult<PyObject> {
+    let value = packed::from_bytes(input)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    Ok(dtxt_value_to_pyobject(py, &value)?.into())
+}
+
+/// Single-pass Python object → packed-bytes walker, the `dumpb` counterpart
+/// to [`serialize_pyobject`]. Writes the same tagged wire format as
+/// [`packed::to_bytes`] directly from the PyObject tree instead of building
+/// an intermediate [`DTXTValue`], since `DTXTValue::Date` only ever borrows
+/// from a parsed source buffer and Python datetimes have none to borrow from.
+fn pyobject_to_packed(
+    obj: &Bound<'_, PyAny>,
+    datetime_cls: &Bound<'_, PyAny>,
+    out: &mut Vec<u8>,
+    visited: &mut HashSet<usize>,
+) -> PyResult<()> {
+    if obj.is_none() {
+        out.push(packed::TAG_NULL);
+        return Ok(());
+    }
+    if let Ok(b) = obj.downcast::<PyBool>() {
+        out.push(if b.is_true() { packed::TAG_TRUE } else { packed::TAG_FALSE });
+        return Ok(());
+    }
+    if let Ok(i) = obj.downcast::<PyInt>() {
+        match i.extract::<i64>() {
+            Ok(n) if n.abs() <= F64_SAFE_INT => {
+                out.push(packed::TAG_NUMBER);
+                out.extend_from_slice(&(n as f64).to_le_bytes());
+            }
+            Ok(n) => {
+                out.push(packed::TAG_BIGINT);
+                packed::write_bigint(out, &BigInt::from(n));
+            }
+            Err(_) => {
+                let digits: String = i.str()?.extract()?;
+                let n: BigInt = digits
+                    .parse()
+                    .map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>("invalid int literal"))?;
+                out.push(packed::TAG_BIGINT);
+                packed::write_bigint(out, &n);
+            }
         }
-        self.advance(); // ]
-        Ok(list)
+        return Ok(());
     }
-
-    fn parse_key(&mut self) -> PyResult<&'a str> {
-        let start = self.pos;
-        let bytes = self.input;
-        let len = bytes.len();
-        let mut i = start;
-        while i < len {
-            let ch = bytes[i];
-            if ch.is_ascii_alphanumeric() || ch == b'_' { i += 1; } else { break; }
+    if let Ok(f) = obj.downcast::<PyFloat>() {
+        out.push(packed::TAG_NUMBER);
+        out.extend_from_slice(&f.value().to_le_bytes());
+        return Ok(());
+    }
+    if let Ok(s) = obj.downcast::<PyString>() {
+        out.push(packed::TAG_STRING);
+        packed::write_str(out, &s.extract::<String>()?);
+        return Ok(());
+    }
+    if let Ok(b) = obj.downcast::<PyBytes>() {
+        out.push(packed::TAG_BYTES);
+        packed::write_uvarint(out, b.as_bytes().len() as u64);
+        out.extend_from_slice(b.as_bytes());
+        return Ok(());
+    }
+    if let Ok(b) = obj.downcast::<PyByteArray>() {
+        let bytes = b.to_vec();
+        out.push(packed::TAG_BYTES);
+        packed::write_uvarint(out, bytes.len() as u64);
+        out.extend_from_slice(&bytes);
+        return Ok(());
+    }
+    if obj.is_instance(datetime_cls)? {
+        let iso: String = obj.call_method0("isoformat")?.extract()?;
+        out.push(packed::TAG_DATE);
+        packed::write_str(out, &iso);
+        return Ok(());
+    }
+    if let Ok(list) = obj.downcast::<PyList>() {
+        let id = list.as_ptr() as usize;
+        if !visited.insert(id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "cyclic reference in dumpb input",
+            ));
         }
-        self.pos = i;
-        Ok(unsafe { std::str::from_utf8_unchecked(&bytes[start..i]) })
+        out.push(packed::TAG_ARRAY);
+        packed::write_uvarint(out, list.len() as u64);
+        for item in list.iter() {
+            pyobject_to_packed(&item, datetime_cls, out, visited)?;
+        }
+        visited.remove(&id);
+        return Ok(());
     }
-
-    fn parse_string(&mut self) -> PyResult<Bound<'py, PyString>> {
-        self.advance(); // `
-        let start = self.pos;
-        if let Some(end) = memchr(b'`', &self.input[start..]) {
-            let abs_end = start + end;
-            self.pos = abs_end + 1;
-            Ok(PyString::new(self.py, unsafe { std::str::from_utf8_unchecked(&self.input[start..abs_end]) }))
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Unterminated string"))
+    if let Ok(tuple) = obj.downcast::<PyTuple>() {
+        let id = tuple.as_ptr() as usize;
+        if !visited.insert(id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "cyclic reference in dumpb input",
+            ));
+        }
+        out.push(packed::TAG_ARRAY);
+        packed::write_uvarint(out, tuple.len() as u64);
+        for item in tuple.iter() {
+            pyobject_to_packed(&item, datetime_cls, out, visited)?;
         }
+        visited.remove(&id);
+        return Ok(());
     }
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        let id = dict.as_ptr() as usize;
+        if !visited.insert(id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "cyclic reference in dumpb input",
+            ));
+        }
+        let mut keys: Vec<String> = dict
+            .keys()
+            .into_iter()
+            .map(|k| k.extract::<String>())
+            .collect::<PyResult<_>>()?;
+        keys.sort_unstable();
 
-    fn parse_number(&mut self) -> PyResult<Bound<'py, PyAny>> {
-        let start = self.pos;
-        while let Some(ch) = self.current() {
-            if ch.is_ascii_digit() || ch == b'.' || ch == b'-' || ch == b'e' || ch == b'E' || ch == b'+' {
-                self.advance();
-            } else { break; }
+        out.push(packed::TAG_OBJECT);
+        packed::write_uvarint(out, keys.len() as u64);
+        for key in &keys {
+            packed::write_str(out, key);
+            let value = dict.get_item(key.as_str())?.ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>("dict key vanished during dumpb")
+            })?;
+            pyobject_to_packed(&value, datetime_cls, out, visited)?;
         }
-        let s = unsafe { std::str::from_utf8_unchecked(&self.input[start..self.pos]) };
-        let n: f64 = s.parse().map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        Ok(n.into_py_any(self.py)?.into_bound(self.py))
+        visited.remove(&id);
+        return Ok(());
     }
+    Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+        "Unsupported type for dumpb: {}",
+        obj.get_type().name()?
+    )))
 }
 
 #[pyfunction]
-fn loads(py: Python<'_>, input: &str) -> PyResult<PyObject> {
-    let mut parser = PyDTXTParser::new(py, input);
-    parser.skip_whitespace();
-    let result = parser.parse_object()?;
-    Ok(result.into())
-}
-
-#[pyfunction]
-fn dumps(obj: PyObject) -> PyResult<String> {
-    // For now, we reuse the existing stringifier by converting back or just implementing a simple python version.
-    // But since the goal is speed and we already have a reference python dumps, 
-    // we could keep Python dumps as is and only use Rust for loads.
-    // However, to be complete:
-    Ok(format!("// Serialized from Rust\n{:?}", obj)) // Placeholder
+fn dumpb(py: Python<'_>, obj: PyObject) -> PyResult<Vec<u8>> {
+    let datetime_cls = py.import("datetime")?.getattr("datetime")?;
+    let mut out = Vec::with_capacity(256);
+    let mut visited = HashSet::new();
+    pyobject_to_packed(obj.bind(py), &datetime_cls, &mut out, &mut visited)?;
+    Ok(out)
 }
 
 #[pymodule]
 fn dtxt_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(loads, m)?)?;
     m.add_function(wrap_pyfunction!(dumps, m)?)?;
+    m.add_function(wrap_pyfunction!(loadb, m)?)?;
+    m.add_function(wrap_pyfunction!(dumpb, m)?)?;
     Ok(())
 }
 
@@ -553,4 +1080,95 @@ mod tests {
             panic!("Expected array");
         }
     }
+
+    #[test]
+    fn test_position_from_offset() {
+        let input = "ab\ncd\nef";
+        assert_eq!(Position::from_offset(input, 0), Position { line: 1, column: 1 });
+        assert_eq!(Position::from_offset(input, 2), Position { line: 1, column: 3 });
+        assert_eq!(Position::from_offset(input, 3), Position { line: 2, column: 1 });
+        assert_eq!(Position::from_offset(input, 7), Position { line: 3, column: 2 });
+    }
+
+    #[test]
+    fn test_parse_error_reports_line_and_column() {
+        let input = "{a: 1,\n b 2}";
+        let err = parse(input).unwrap_err();
+        assert_eq!(err.position, Position { line: 2, column: 4 });
+        assert!(err.to_string().contains("2:4"));
+    }
+
+    #[test]
+    fn test_string_without_escapes_is_borrowed() {
+        let input = "{name: `John`}";
+        let result = parse(input).unwrap();
+        match result.get("name") {
+            Some(DTXTValue::String(Cow::Borrowed(s))) => assert_eq!(*s, "John"),
+            other => panic!("expected a borrowed string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_string_with_escapes_round_trips() {
+        let input = r#"{greeting: `hi\n\tthere\\\`friend\``}"#;
+        let result = parse(input).unwrap();
+        match result.get("greeting") {
+            Some(DTXTValue::String(s)) => assert_eq!(s.as_ref(), "hi\n\tthere\\`friend`"),
+            other => panic!("expected a string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_string_with_unicode_escape() {
+        let input = r#"{heart: `\u{2764}`}"#;
+        let result = parse(input).unwrap();
+        match result.get("heart") {
+            Some(DTXTValue::String(s)) => assert_eq!(s.as_ref(), "\u{2764}"),
+            other => panic!("expected a string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_string_with_invalid_unicode_escape_errors() {
+        let input = r#"{bad: `\u{ffffffff}`}"#;
+        let err = parse(input).unwrap_err();
+        assert!(matches!(err.code, DTXTError::InvalidEscape(_)));
+    }
+
+    #[test]
+    fn test_stringify_escapes_special_characters() {
+        let value = DTXTValue::String(Cow::Borrowed("line1\nline2\t`tick`"));
+        let out = stringify(&value, None);
+        assert_eq!(out, r"`line1\nline2\t\`tick\``");
+    }
+
+    #[test]
+    fn test_bn_fits_in_i64() {
+        let input = "{n: BN(123456789012345)}";
+        let result = parse(input).unwrap();
+        assert_eq!(result.get("n"), Some(&DTXTValue::BigInt(BigInt::from(123456789012345i64))));
+    }
+
+    #[test]
+    fn test_bn_beyond_i64_is_not_truncated() {
+        let input = "{n: BN(123456789012345678901234567890)}";
+        let result = parse(input).unwrap();
+        let expected: BigInt = "123456789012345678901234567890".parse().unwrap();
+        assert_eq!(result.get("n"), Some(&DTXTValue::BigInt(expected)));
+    }
+
+    #[test]
+    fn test_bn_negative_beyond_i64_round_trips_through_stringify() {
+        let input = "{n: BN(-123456789012345678901234567890)}";
+        let result = parse(input).unwrap();
+        let value = result.get("n").unwrap();
+        assert_eq!(stringify(value, None), "BN(-123456789012345678901234567890)");
+    }
+
+    #[test]
+    fn test_bn_invalid_payload_errors() {
+        let input = "{n: BN(not_a_number)}";
+        let err = parse(input).unwrap_err();
+        assert!(matches!(err.code, DTXTError::InvalidConstructor(_)));
+    }
 }
@@ -1,93 +1,189 @@
-use dsf::{DSFValue, parse, stringify};
-use std::time::Instant;
+use dsf::dsf::DSFValue;
+use dsf::dsf_serde::to_string;
+use dsf::dsf_stream::{DsfStreamReader, DsfStreamWriter};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::fs;
 use std::fs::File;
-use std::io::Write;
-
-fn generate_large_data(count: usize) -> HashMap<String, DSFValue> {
-    let mut entries = Vec::new();
-    for i in 0..count {
-        let mut meta = HashMap::new();
-        meta.insert("level".to_string(), DSFValue::Number((i % 10) as f64));
-        meta.insert("verified".to_string(), DSFValue::Bool(i % 3 == 0));
-        meta.insert("note".to_string(), DSFValue::Null);
-
-        let mut nested = HashMap::new();
-        nested.insert("a".to_string(), DSFValue::Number(1.0));
-        nested.insert("b".to_string(), DSFValue::Bool(false));
-        nested.insert("c".to_string(), DSFValue::String("nested string".to_string()));
-        meta.insert("nested".to_string(), DSFValue::Object(nested));
-
-        let mut entry = HashMap::new();
-        entry.insert("id".to_string(), DSFValue::Number(i as f64));
-        entry.insert("uid".to_string(), DSFValue::String(format!("user-{}", i)));
-        entry.insert("isActive".to_string(), DSFValue::Bool(i % 2 == 0));
-        entry.insert("score".to_string(), DSFValue::Number(rand::random::<f64>() * 1000.0));
-        entry.insert("tags".to_string(), DSFValue::Array(vec![
-            DSFValue::String("data".to_string()),
-            DSFValue::String("benchmark".to_string()),
-            DSFValue::String("storage".to_string()),
-            DSFValue::String("json".to_string()),
-            DSFValue::String("dsf".to_string()),
-        ]));
-        entry.insert("meta".to_string(), DSFValue::Object(meta));
-        entries.push(DSFValue::Object(entry));
+use std::io::{BufReader, Write};
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Build one benchmark entry, the unit a real ND-DSF log would append one
+/// of per line, so the benchmark can stream entries through
+/// [`DsfStreamWriter`]/[`DsfStreamReader`] instead of holding all of them in
+/// one giant root value.
+fn generate_entry(i: usize) -> DSFValue {
+    let mut meta = HashMap::new();
+    meta.insert("level".to_string(), DSFValue::Number((i % 10) as f64));
+    meta.insert("verified".to_string(), DSFValue::Bool(i % 3 == 0));
+    meta.insert("note".to_string(), DSFValue::Null);
+
+    let mut nested = HashMap::new();
+    nested.insert("a".to_string(), DSFValue::Number(1.0));
+    nested.insert("b".to_string(), DSFValue::Bool(false));
+    nested.insert("c".to_string(), DSFValue::String("nested string".to_string()));
+    meta.insert("nested".to_string(), DSFValue::Object(nested));
+
+    let mut entry = HashMap::new();
+    entry.insert("id".to_string(), DSFValue::Number(i as f64));
+    entry.insert("uid".to_string(), DSFValue::String(format!("user-{}", i)));
+    entry.insert("isActive".to_string(), DSFValue::Bool(i % 2 == 0));
+    entry.insert("score".to_string(), DSFValue::Number(rand::random::<f64>() * 1000.0));
+    entry.insert("tags".to_string(), DSFValue::Array(vec![
+        DSFValue::String("data".to_string()),
+        DSFValue::String("benchmark".to_string()),
+        DSFValue::String("storage".to_string()),
+        DSFValue::String("json".to_string()),
+        DSFValue::String("dsf".to_string()),
+    ]));
+    entry.insert("meta".to_string(), DSFValue::Object(meta));
+    DSFValue::Object(entry)
+}
+
+/// One completed measurement run: `sample_count` wall-clock durations (in
+/// milliseconds) for a single operation (e.g. "parse") over a fixed-size
+/// input. Flattened so every computed statistic is a top-level field, which
+/// keeps the serialized record easy to load into a database or diff across
+/// commits.
+#[derive(Serialize)]
+struct BenchRecord {
+    run_name: String,
+    run_id: String,
+    operation: String,
+    dataset_entries: usize,
+    input_bytes: usize,
+    sample_count: usize,
+    mean_ms: f64,
+    median_ms: f64,
+    variance_ms2: f64,
+    min_ms: f64,
+    max_ms: f64,
+}
+
+/// Collects per-call durations for one operation and reduces them to a
+/// [`BenchRecord`] once sampling is done.
+struct Measurement {
+    operation: String,
+    samples: Vec<f64>,
+}
+
+impl Measurement {
+    fn new(operation: &str) -> Self {
+        Self {
+            operation: operation.to_string(),
+            samples: Vec::new(),
+        }
     }
 
-    let mut root = HashMap::new();
-    root.insert("title".to_string(), DSFValue::String("DSF vs JSON (Rust)".to_string()));
-    root.insert("description".to_string(), DSFValue::String("Benchmark for base format overhead".to_string()));
-    root.insert("entries".to_string(), DSFValue::Array(entries));
+    fn record<T>(&mut self, mut f: impl FnMut() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.samples.push(start.elapsed().as_secs_f64() * 1000.0);
+        result
+    }
 
-    root
+    fn finish(self, run_name: &str, run_id: &str, dataset_entries: usize, input_bytes: usize) -> BenchRecord {
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let count = sorted.len();
+
+        let mean = sorted.iter().sum::<f64>() / count as f64;
+        let median = if count.is_multiple_of(2) {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2.0
+        } else {
+            sorted[count / 2]
+        };
+        let variance = sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count as f64;
+        let min = sorted.first().copied().unwrap_or(0.0);
+        let max = sorted.last().copied().unwrap_or(0.0);
+
+        BenchRecord {
+            run_name: run_name.to_string(),
+            run_id: run_id.to_string(),
+            operation: self.operation,
+            dataset_entries,
+            input_bytes,
+            sample_count: count,
+            mean_ms: mean,
+            median_ms: median,
+            variance_ms2: variance,
+            min_ms: min,
+            max_ms: max,
+        }
+    }
+}
+
+/// Serialize one [`BenchRecord`] to its own file under `bench_results/`,
+/// named by run name + a fresh uuid, so successive runs can be diffed over
+/// time instead of overwriting each other.
+fn write_record(record: &BenchRecord) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all("bench_results")?;
+    let path = format!("bench_results/{}_{}_{}.dsf", record.run_name, record.operation, record.run_id);
+    let mut f = File::create(&path)?;
+    f.write_all(to_string(record)?.as_bytes())?;
+    println!("wrote {}", path);
+    Ok(())
 }
 
 const DATASET_SIZE: usize = 30000;
+const ITERATIONS: usize = 5;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let run_id = Uuid::new_v4().to_string();
+    let run_name = "dsf-vs-json-rust";
+
     println!("Generating dataset with {} entries...", DATASET_SIZE);
-    let raw_data = generate_large_data(DATASET_SIZE);
-    let root_value = DSFValue::Object(raw_data.clone());
-
-    // Prepare JSON data
-    // We'll use a simple approach to get JSON since we removed serde from DSFValue
-    // For benchmark purposes, we can just use a placeholder or convert manually.
-    // Actually, let's just use the previous JSON string if it exists, or generate a simple one.
-    // But we need a fair comparison. Let's use a dummy JSON for now or implement a quick conversion.
-    
-    let dsf_str = stringify(&root_value, None);
-    let dsf_size = dsf_str.len();
-
-    // Re-run Go benchmark to get JSON string if needed, or just assume it's similar.
-    // Let's just focus on DSF performance here as Rust JSON is already known to be fast.
-    
+    let entries: Vec<DSFValue> = (0..DATASET_SIZE).map(generate_entry).collect();
+
+    let mut stream_buf = Vec::new();
+    {
+        let mut writer = DsfStreamWriter::new(&mut stream_buf);
+        for entry in &entries {
+            writer.write_value(entry)?;
+        }
+        writer.flush()?;
+    }
+    let dsf_size = stream_buf.len();
+
     println!("\n--- Payload Size ---");
     println!("DSF:  {:.2} MB", dsf_size as f64 / 1024.0 / 1024.0);
 
-    let iterations = 5;
-
-    println!("\n--- Parsing Performance (Average of {} runs) ---", iterations);
-
-    let mut dsf_parse_total = 0.0;
-    for _ in 0..iterations {
-        let start = Instant::now();
-        let _ = parse(&dsf_str)?;
-        dsf_parse_total += start.elapsed().as_secs_f64() * 1000.0;
+    let mut parse_measurement = Measurement::new("parse");
+    for _ in 0..ITERATIONS {
+        parse_measurement.record(|| {
+            let reader = DsfStreamReader::new(BufReader::new(stream_buf.as_slice()));
+            for entry in reader {
+                entry.unwrap();
+            }
+        });
     }
-    println!("dsf-rs:     {:.2} ms", dsf_parse_total / iterations as f64);
-
-    println!("\n--- Serialization Performance (Average of {} runs) ---", iterations);
-
-    let mut dsf_stringify_total = 0.0;
-    for _ in 0..iterations {
-        let start = Instant::now();
-        let _ = stringify(&root_value, None);
-        dsf_stringify_total += start.elapsed().as_secs_f64() * 1000.0;
+    let parse_record = parse_measurement.finish(run_name, &run_id, DATASET_SIZE, dsf_size);
+    println!(
+        "\n--- Parsing Performance ({} runs) ---\ndsf-rs: mean {:.2}ms median {:.2}ms min {:.2}ms max {:.2}ms",
+        parse_record.sample_count, parse_record.mean_ms, parse_record.median_ms, parse_record.min_ms, parse_record.max_ms
+    );
+    write_record(&parse_record)?;
+
+    let mut stringify_measurement = Measurement::new("stringify");
+    for _ in 0..ITERATIONS {
+        stringify_measurement.record(|| {
+            let mut out = Vec::with_capacity(dsf_size);
+            let mut writer = DsfStreamWriter::new(&mut out);
+            for entry in &entries {
+                writer.write_value(entry).unwrap();
+            }
+        });
     }
-    println!("dsf-rs:     {:.2} ms", dsf_stringify_total / iterations as f64);
+    let stringify_record = stringify_measurement.finish(run_name, &run_id, DATASET_SIZE, dsf_size);
+    println!(
+        "\n--- Serialization Performance ({} runs) ---\ndsf-rs: mean {:.2}ms median {:.2}ms min {:.2}ms max {:.2}ms",
+        stringify_record.sample_count, stringify_record.mean_ms, stringify_record.median_ms, stringify_record.min_ms, stringify_record.max_ms
+    );
+    write_record(&stringify_record)?;
 
     let mut f_dsf = File::create("bench_v2_rs_updated.dsf")?;
-    f_dsf.write_all(dsf_str.as_bytes())?;
+    f_dsf.write_all(&stream_buf)?;
 
     Ok(())
 }
@@ -0,0 +1,305 @@
+//! Binary/packed codec for [`crate::DTXTValue`] (see `lib.rs`): a compact
+//! wire format for network/storage use, alongside the human-readable DTXT
+//! text format used for config. `to_bytes`/`from_bytes` round-trip the exact
+//! same `DTXTValue` variants as the text parser, just faster to produce and
+//! parse.
+//!
+//! Layout is a one-byte tag per value followed by its payload. Lengths and
+//! counts are LEB128 varints so small arrays/objects stay tiny:
+//!
+//! ```text
+//! 0 = Null
+//! 1 = False
+//! 2 = True
+//! 3 = Number:  f64, little-endian
+//! 4 = String:  varint length, UTF-8 bytes
+//! 5 = BigInt:  sign byte (0 = non-negative, 1 = negative), varint length,
+//!              big-endian magnitude bytes
+//! 6 = Bytes:   varint length, raw bytes
+//! 7 = Date:    varint length, UTF-8 bytes
+//! 8 = Array:   varint count, then that many values
+//! 9 = Object:  varint count, then that many (key, value) pairs;
+//!              keys are encoded like a tag-4 payload (no leading tag byte)
+//! ```
+use std::borrow::Cow;
+use std::fmt;
+
+use num_bigint::{BigInt, Sign};
+use rustc_hash::FxHashMap;
+
+use crate::DTXTValue;
+
+pub(crate) const TAG_NULL: u8 = 0;
+pub(crate) const TAG_FALSE: u8 = 1;
+pub(crate) const TAG_TRUE: u8 = 2;
+pub(crate) const TAG_NUMBER: u8 = 3;
+pub(crate) const TAG_STRING: u8 = 4;
+pub(crate) const TAG_BIGINT: u8 = 5;
+pub(crate) const TAG_BYTES: u8 = 6;
+pub(crate) const TAG_DATE: u8 = 7;
+pub(crate) const TAG_ARRAY: u8 = 8;
+pub(crate) const TAG_OBJECT: u8 = 9;
+
+#[derive(Debug)]
+pub enum PackedError {
+    UnexpectedEOF,
+    InvalidTag(u8),
+    InvalidUtf8,
+    VarintOverflow,
+}
+
+impl fmt::Display for PackedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackedError::UnexpectedEOF => write!(f, "Unexpected end of packed data"),
+            PackedError::InvalidTag(tag) => write!(f, "Invalid packed tag byte: {}", tag),
+            PackedError::InvalidUtf8 => write!(f, "Invalid UTF-8 in packed string"),
+            PackedError::VarintOverflow => write!(f, "Varint too large"),
+        }
+    }
+}
+
+impl std::error::Error for PackedError {}
+
+pub(crate) fn write_uvarint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn read_uvarint(input: &[u8], pos: &mut usize) -> Result<u64, PackedError> {
+    let mut result: u64 = 0;
+    let mut shift = 0u32;
+    loop {
+        let byte = *input.get(*pos).ok_or(PackedError::UnexpectedEOF)?;
+        *pos += 1;
+        if shift >= 64 {
+            return Err(PackedError::VarintOverflow);
+        }
+        result |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+    }
+}
+
+pub(crate) fn write_bigint(out: &mut Vec<u8>, n: &BigInt) {
+    out.push(if n.sign() == Sign::Minus { 1 } else { 0 });
+    let (_, magnitude) = n.to_bytes_be();
+    write_uvarint(out, magnitude.len() as u64);
+    out.extend_from_slice(&magnitude);
+}
+
+fn read_bigint(input: &[u8], pos: &mut usize) -> Result<BigInt, PackedError> {
+    let sign_byte = *input.get(*pos).ok_or(PackedError::UnexpectedEOF)?;
+    *pos += 1;
+    let len = read_uvarint(input, pos)? as usize;
+    let end = pos.checked_add(len).ok_or(PackedError::UnexpectedEOF)?;
+    let magnitude = input.get(*pos..end).ok_or(PackedError::UnexpectedEOF)?;
+    *pos = end;
+    let sign = if sign_byte == 1 { Sign::Minus } else { Sign::Plus };
+    Ok(BigInt::from_bytes_be(sign, magnitude))
+}
+
+pub(crate) fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_uvarint(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn read_str<'a>(input: &'a [u8], pos: &mut usize) -> Result<&'a str, PackedError> {
+    let len = read_uvarint(input, pos)? as usize;
+    let end = pos.checked_add(len).ok_or(PackedError::UnexpectedEOF)?;
+    let bytes = input.get(*pos..end).ok_or(PackedError::UnexpectedEOF)?;
+    *pos = end;
+    std::str::from_utf8(bytes).map_err(|_| PackedError::InvalidUtf8)
+}
+
+fn write_value(value: &DTXTValue, out: &mut Vec<u8>) {
+    match value {
+        DTXTValue::Null => out.push(TAG_NULL),
+        DTXTValue::Bool(false) => out.push(TAG_FALSE),
+        DTXTValue::Bool(true) => out.push(TAG_TRUE),
+        DTXTValue::Number(n) => {
+            out.push(TAG_NUMBER);
+            out.extend_from_slice(&n.to_le_bytes());
+        }
+        DTXTValue::String(s) => {
+            out.push(TAG_STRING);
+            write_str(out, s);
+        }
+        DTXTValue::BigInt(n) => {
+            out.push(TAG_BIGINT);
+            write_bigint(out, n);
+        }
+        DTXTValue::Bytes(bytes) => {
+            out.push(TAG_BYTES);
+            write_uvarint(out, bytes.len() as u64);
+            out.extend_from_slice(bytes);
+        }
+        DTXTValue::Date(s) => {
+            out.push(TAG_DATE);
+            write_str(out, s);
+        }
+        DTXTValue::Array(arr) => {
+            out.push(TAG_ARRAY);
+            write_uvarint(out, arr.len() as u64);
+            for item in arr {
+                write_value(item, out);
+            }
+        }
+        DTXTValue::Object(map) => {
+            out.push(TAG_OBJECT);
+            write_uvarint(out, map.len() as u64);
+            for (key, value) in map {
+                write_str(out, key);
+                write_value(value, out);
+            }
+        }
+    }
+}
+
+fn read_value<'a>(input: &'a [u8], pos: &mut usize) -> Result<DTXTValue<'a>, PackedError> {
+    let tag = *input.get(*pos).ok_or(PackedError::UnexpectedEOF)?;
+    *pos += 1;
+    match tag {
+        TAG_NULL => Ok(DTXTValue::Null),
+        TAG_FALSE => Ok(DTXTValue::Bool(false)),
+        TAG_TRUE => Ok(DTXTValue::Bool(true)),
+        TAG_NUMBER => {
+            let end = pos.checked_add(8).ok_or(PackedError::UnexpectedEOF)?;
+            let bytes: [u8; 8] = input
+                .get(*pos..end)
+                .ok_or(PackedError::UnexpectedEOF)?
+                .try_into()
+                .map_err(|_| PackedError::UnexpectedEOF)?;
+            *pos = end;
+            Ok(DTXTValue::Number(f64::from_le_bytes(bytes)))
+        }
+        TAG_STRING => Ok(DTXTValue::String(Cow::Borrowed(read_str(input, pos)?))),
+        TAG_BIGINT => Ok(DTXTValue::BigInt(read_bigint(input, pos)?)),
+        TAG_BYTES => {
+            let len = read_uvarint(input, pos)? as usize;
+            let end = pos.checked_add(len).ok_or(PackedError::UnexpectedEOF)?;
+            let bytes = input.get(*pos..end).ok_or(PackedError::UnexpectedEOF)?.to_vec();
+            *pos = end;
+            Ok(DTXTValue::Bytes(bytes))
+        }
+        TAG_DATE => Ok(DTXTValue::Date(read_str(input, pos)?)),
+        TAG_ARRAY => {
+            let count = read_uvarint(input, pos)? as usize;
+            let mut arr = Vec::with_capacity(count);
+            for _ in 0..count {
+                arr.push(read_value(input, pos)?);
+            }
+            Ok(DTXTValue::Array(arr))
+        }
+        TAG_OBJECT => {
+            let count = read_uvarint(input, pos)? as usize;
+            let mut map = FxHashMap::default();
+            map.reserve(count);
+            for _ in 0..count {
+                let key = read_str(input, pos)?;
+                let value = read_value(input, pos)?;
+                map.insert(key, value);
+            }
+            Ok(DTXTValue::Object(map))
+        }
+        other => Err(PackedError::InvalidTag(other)),
+    }
+}
+
+/// Encode a [`DTXTValue`] into the packed binary wire format.
+pub fn to_bytes(value: &DTXTValue) -> Vec<u8> {
+    let mut out = Vec::with_capacity(256);
+    write_value(value, &mut out);
+    out
+}
+
+/// Decode a [`DTXTValue`] previously produced by [`to_bytes`]. Borrows
+/// strings and byte slices directly out of `input`, the same way the text
+/// parser borrows out of its source `&str`.
+pub fn from_bytes(input: &[u8]) -> Result<DTXTValue<'_>, PackedError> {
+    let mut pos = 0;
+    let value = read_value(input, &mut pos)?;
+    if pos != input.len() {
+        return Err(PackedError::UnexpectedEOF);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hash::FxHashMap;
+
+    #[test]
+    fn test_scalar_roundtrip() {
+        for value in [
+            DTXTValue::Null,
+            DTXTValue::Bool(true),
+            DTXTValue::Bool(false),
+            DTXTValue::Number(3.5),
+            DTXTValue::String(Cow::Borrowed("hi")),
+            DTXTValue::BigInt(BigInt::from(-123456789012345i64)),
+            DTXTValue::Date("2024-01-01T00:00:00Z"),
+            DTXTValue::Bytes(vec![0xDE, 0xAD, 0xBE, 0xEF]),
+        ] {
+            let bytes = to_bytes(&value);
+            assert_eq!(from_bytes(&bytes).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_bigint_beyond_i64_roundtrips() {
+        let huge: BigInt = "123456789012345678901234567890".parse().unwrap();
+        let value = DTXTValue::BigInt(-huge);
+        let bytes = to_bytes(&value);
+        assert_eq!(from_bytes(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn test_bigint_zero_roundtrips() {
+        let value = DTXTValue::BigInt(BigInt::from(0));
+        let bytes = to_bytes(&value);
+        assert_eq!(from_bytes(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn test_nested_roundtrip() {
+        let mut map = FxHashMap::default();
+        map.insert("a", DTXTValue::Number(1.0));
+        map.insert(
+            "b",
+            DTXTValue::Array(vec![DTXTValue::Bool(true), DTXTValue::Null]),
+        );
+        let value = DTXTValue::Object(map);
+        let bytes = to_bytes(&value);
+        assert_eq!(from_bytes(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn test_small_arrays_and_objects_stay_tiny() {
+        let bytes = to_bytes(&DTXTValue::Array(vec![DTXTValue::Bool(true)]));
+        // tag + varint(count=1) + tag = 3 bytes
+        assert_eq!(bytes.len(), 3);
+    }
+
+    #[test]
+    fn test_rejects_unknown_tag() {
+        assert!(matches!(from_bytes(&[0xFF]), Err(PackedError::InvalidTag(0xFF))));
+    }
+
+    #[test]
+    fn test_rejects_trailing_data() {
+        let mut bytes = to_bytes(&DTXTValue::Null);
+        bytes.push(0);
+        assert!(from_bytes(&bytes).is_err());
+    }
+}